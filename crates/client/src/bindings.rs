@@ -0,0 +1,302 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// How often the bindings file is checked for edits.
+const RELOAD_INTERVAL_SECS: f32 = 1.0;
+
+/// Loads the key and gamepad bindings at startup and reloads them whenever
+/// the file changes on disk. A missing file is written out with the
+/// defaults once the app starts.
+pub struct BindingsPlugin {
+    pub path: PathBuf,
+}
+
+impl Plugin for BindingsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut file = BindingsFile::new(self.path.clone());
+        let bindings = file.load().unwrap_or_default();
+        app.insert_resource(bindings);
+        app.insert_resource(file);
+        app.add_systems(Startup, write_default_bindings);
+        app.add_systems(Update, reload_bindings);
+    }
+}
+
+/// Every control the client reads, saved as RON. Fields missing from the
+/// file keep their defaults.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct Bindings {
+    /// Keys steering the first ship: Red when one player flies both.
+    /// Flying a single ship, both key sets steer it.
+    pub keys: ShipKeys,
+    /// Keys steering the second ship: Blue when one player flies both.
+    pub second_keys: ShipKeys,
+    /// Stick steering the ship: Red's in solo mode.
+    pub stick: Stick,
+    /// Stick steering Blue in solo mode.
+    pub second_stick: Stick,
+    /// Stick aiming and firing the ship. Solo mode steers with both sticks,
+    /// so it aims with the mouse only.
+    pub aim_stick: Stick,
+    /// Mouse button that fires toward the cursor while held.
+    pub fire_mouse: MouseButton,
+    /// Stick travel ignored around the center, from 0 to 1.
+    pub deadzone: f32,
+    /// Exponent applied to stick travel past the deadzone: 1 is linear,
+    /// higher gives finer control near the center.
+    pub response_curve: f32,
+    /// Flip the ready flag in the lobby.
+    pub ready: Buttons,
+    /// Ask for the other color in the lobby.
+    pub swap_color: Buttons,
+    /// Ask for a rematch after a game over.
+    pub rematch: Buttons,
+    /// Swap which ship each gamepad steers in couch co-op.
+    pub swap_pads: Buttons,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            keys: ShipKeys {
+                up: vec![KeyCode::KeyW],
+                down: vec![KeyCode::KeyS],
+                left: vec![KeyCode::KeyA],
+                right: vec![KeyCode::KeyD],
+            },
+            second_keys: ShipKeys {
+                up: vec![KeyCode::ArrowUp],
+                down: vec![KeyCode::ArrowDown],
+                left: vec![KeyCode::ArrowLeft],
+                right: vec![KeyCode::ArrowRight],
+            },
+            stick: Stick {
+                x: GamepadAxis::LeftStickX,
+                y: GamepadAxis::LeftStickY,
+            },
+            second_stick: Stick {
+                x: GamepadAxis::RightStickX,
+                y: GamepadAxis::RightStickY,
+            },
+            aim_stick: Stick {
+                x: GamepadAxis::RightStickX,
+                y: GamepadAxis::RightStickY,
+            },
+            fire_mouse: MouseButton::Left,
+            deadzone: 0.2,
+            response_curve: 1.0,
+            ready: Buttons::new(KeyCode::Enter, GamepadButton::Start),
+            swap_color: Buttons::new(KeyCode::KeyC, GamepadButton::West),
+            rematch: Buttons::new(KeyCode::KeyR, GamepadButton::South),
+            swap_pads: Buttons {
+                keys: Vec::new(),
+                buttons: vec![GamepadButton::Select],
+            },
+        }
+    }
+}
+
+impl Bindings {
+    /// Direction of `stick` on `gamepad`, with the deadzone and response
+    /// curve applied. `None` while the stick rests in the deadzone.
+    pub fn read_stick(&self, stick: &Stick, gamepad: &Gamepad) -> Option<Vec2> {
+        let raw = Vec2::new(
+            gamepad.get(stick.x).unwrap_or(0.0),
+            gamepad.get(stick.y).unwrap_or(0.0),
+        );
+        let travel = raw.length().min(1.0);
+        if travel <= self.deadzone {
+            return None;
+        }
+        let scaled = ((travel - self.deadzone) / (1.0 - self.deadzone)).powf(self.response_curve);
+        Some(raw.normalize() * scaled)
+    }
+
+    /// Replace a `deadzone` outside [0, 1) or a `response_curve` that isn't
+    /// positive with the default, warning about each. Either would break
+    /// [`Bindings::read_stick`].
+    fn checked(mut self) -> Self {
+        let defaults = Self::default();
+        if !(0.0..1.0).contains(&self.deadzone) {
+            warn!(
+                "Bindings deadzone {} is outside [0, 1), using {}",
+                self.deadzone, defaults.deadzone
+            );
+            self.deadzone = defaults.deadzone;
+        }
+        if !(self.response_curve > 0.0 && self.response_curve.is_finite()) {
+            warn!(
+                "Bindings response curve {} is not positive, using {}",
+                self.response_curve, defaults.response_curve
+            );
+            self.response_curve = defaults.response_curve;
+        }
+        self
+    }
+}
+
+/// Keys for each direction a ship can be steered in.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ShipKeys {
+    pub up: Vec<KeyCode>,
+    pub down: Vec<KeyCode>,
+    pub left: Vec<KeyCode>,
+    pub right: Vec<KeyCode>,
+}
+
+impl ShipKeys {
+    pub fn direction(&self, keys: &ButtonInput<KeyCode>) -> Vec2 {
+        let mut dir = Vec2::ZERO;
+        if keys.any_pressed(self.up.iter().copied()) {
+            dir.y += 1.0;
+        }
+        if keys.any_pressed(self.down.iter().copied()) {
+            dir.y -= 1.0;
+        }
+        if keys.any_pressed(self.left.iter().copied()) {
+            dir.x -= 1.0;
+        }
+        if keys.any_pressed(self.right.iter().copied()) {
+            dir.x += 1.0;
+        }
+        dir
+    }
+}
+
+/// The pair of gamepad axes that make up a stick.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Stick {
+    pub x: GamepadAxis,
+    pub y: GamepadAxis,
+}
+
+/// Keys and gamepad buttons that all trigger the same action.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Buttons {
+    pub keys: Vec<KeyCode>,
+    pub buttons: Vec<GamepadButton>,
+}
+
+impl Buttons {
+    fn new(key: KeyCode, button: GamepadButton) -> Self {
+        Self {
+            keys: vec![key],
+            buttons: vec![button],
+        }
+    }
+
+    /// What's bound, e.g. "Enter, Start", or "-" for nothing.
+    pub fn describe(&self) -> String {
+        let keys = self.keys.iter().map(|key| format!("{key:?}"));
+        let pads = self.buttons.iter().map(|button| format!("{button:?}"));
+        let names: Vec<String> = keys.chain(pads).collect();
+        if names.is_empty() {
+            "-".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+
+    /// Whether the keyboard or any of `gamepads` pressed this action this frame.
+    pub fn just_pressed<'a>(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        gamepads: impl IntoIterator<Item = &'a Gamepad>,
+    ) -> bool {
+        keys.any_just_pressed(self.keys.iter().copied())
+            || gamepads
+                .into_iter()
+                .any(|gamepad| gamepad.any_just_pressed(self.buttons.iter().copied()))
+    }
+}
+
+/// Where the bindings live, and the modification time they were last read
+/// or written at.
+#[derive(Resource)]
+pub struct BindingsFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl BindingsFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            modified: None,
+            timer: Timer::from_seconds(RELOAD_INTERVAL_SECS, TimerMode::Repeating),
+        }
+    }
+
+    fn modified_on_disk(&self) -> Option<SystemTime> {
+        std::fs::metadata(&self.path)
+            .and_then(|meta| meta.modified())
+            .ok()
+    }
+
+    /// Read the bindings, with out of range stick settings reset. `None`
+    /// when the file is missing or unreadable, which is logged.
+    pub fn load(&mut self) -> Option<Bindings> {
+        self.modified = self.modified_on_disk();
+        let ron = match std::fs::read_to_string(&self.path) {
+            Ok(ron) => ron,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(err) => {
+                warn!("Could not read bindings {}: {}", self.path.display(), err);
+                return None;
+            }
+        };
+        match ron::from_str(&ron) {
+            Ok(bindings) => Some(Bindings::checked(bindings)),
+            Err(err) => {
+                warn!(
+                    "Ignoring unreadable bindings {}: {}",
+                    self.path.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
+    /// Write `bindings` out, e.g. after rebinding in game.
+    pub fn save(&mut self, bindings: &Bindings) {
+        let saved = ron::ser::to_string_pretty(bindings, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)
+            .and_then(|ron| std::fs::write(&self.path, ron));
+        if let Err(err) = saved {
+            error!("Failed to save bindings: {}", err);
+        }
+        // Our own write isn't an edit to pick up
+        self.modified = self.modified_on_disk();
+    }
+}
+
+/// Leave a file with the defaults to edit when there is none yet.
+fn write_default_bindings(mut file: ResMut<BindingsFile>, bindings: Res<Bindings>) {
+    if !file.path.exists() {
+        file.save(&bindings);
+    }
+}
+
+/// Pick up edits to the bindings file while the game runs. A file that no
+/// longer parses keeps the current bindings.
+fn reload_bindings(
+    mut file: ResMut<BindingsFile>,
+    mut bindings: ResMut<Bindings>,
+    time: Res<Time>,
+) {
+    if !file.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    if file.modified_on_disk() == file.modified {
+        return;
+    }
+    if let Some(reloaded) = file.load() {
+        info!("Reloaded bindings from {}", file.path.display());
+        *bindings = reloaded;
+    }
+}
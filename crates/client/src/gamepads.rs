@@ -0,0 +1,163 @@
+use bevy::prelude::*;
+use schizoid_shared::components::{MatchState, TeamColor};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::bindings::Bindings;
+use crate::rebind::menu_closed;
+
+/// Couch co-op: each connected gamepad steers one ship, and the keyboard
+/// steers whichever ships have no gamepad. Which pad flies which color is
+/// remembered by the pad's name across launches, identical pads told apart
+/// by the order they connect in.
+pub struct GamepadAssignmentPlugin {
+    /// File the assignments are kept in.
+    pub path: PathBuf,
+}
+
+impl Plugin for GamepadAssignmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PadAssignments::load(self.path.clone()));
+        app.add_observer(assign_gamepad);
+        app.add_systems(Update, swap_pad_color.run_if(menu_closed));
+    }
+}
+
+/// The ship color a connected gamepad steers in couch co-op.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlaysAs(pub TeamColor);
+
+/// What a connected gamepad's color is remembered by; see
+/// [`PadAssignments::key`].
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct PadKey(pub String);
+
+/// Color each gamepad (by key) last played as, saved as JSON after every
+/// change.
+#[derive(Resource, Debug, Default)]
+pub struct PadAssignments {
+    path: PathBuf,
+    colors: HashMap<String, TeamColor>,
+}
+
+impl PadAssignments {
+    /// Load the assignments from `path`. A missing or unreadable file starts
+    /// empty and is replaced on the next save.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let colors = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self { path, colors }
+    }
+
+    /// Key for a pad called `name`, given the keys of the pads already
+    /// connected: its name, numbered from the second of several identical
+    /// pads ("Xbox Controller #2"), so each keeps its own color.
+    pub fn key(name: &str, connected: &[&str]) -> String {
+        (1..)
+            .map(|ordinal| match ordinal {
+                1 => name.to_string(),
+                _ => format!("{name} #{ordinal}"),
+            })
+            .find(|key| !connected.contains(&key.as_str()))
+            .expect("some ordinal is free")
+    }
+
+    /// The color the pad keyed `key` last played as, if any.
+    pub fn color(&self, key: &str) -> Option<TeamColor> {
+        self.colors.get(key).copied()
+    }
+
+    /// The color the pad keyed `key` should steer when other pads already
+    /// steer `taken`: its last one if free, else Blue first, leaving Red to
+    /// the keyboard. `None` when both ships have a pad.
+    pub fn pick(&self, key: &str, taken: &[TeamColor]) -> Option<TeamColor> {
+        self.color(key)
+            .into_iter()
+            .chain([TeamColor::Blue, TeamColor::Red])
+            .find(|color| !taken.contains(color))
+    }
+
+    /// Remember that the pad keyed `key` plays as `color`.
+    pub fn set(&mut self, key: &str, color: TeamColor) {
+        if self.color(key) == Some(color) {
+            return;
+        }
+        self.colors.insert(key.to_string(), color);
+        let saved = serde_json::to_string_pretty(&self.colors)
+            .map_err(std::io::Error::from)
+            .and_then(|json| std::fs::write(&self.path, json));
+        if let Err(err) = saved {
+            error!("Failed to save gamepad assignments: {}", err);
+        }
+    }
+
+    /// Swap the pad keyed `key` from `color` to the other ship, remembering
+    /// it. Returns the color it steers now.
+    pub fn swap(&mut self, key: &str, color: TeamColor) -> TeamColor {
+        let swapped = color.opposite();
+        self.set(key, swapped);
+        swapped
+    }
+}
+
+/// Give a newly connected gamepad the color it played as last time, unless
+/// another pad already has it. A new pad takes Blue first, leaving Red to
+/// the keyboard.
+fn assign_gamepad(
+    trigger: On<Add, Gamepad>,
+    names: Query<&Name>,
+    pads: Query<(&PadKey, Option<&PlaysAs>), With<Gamepad>>,
+    mut assignments: ResMut<PadAssignments>,
+    mut commands: Commands,
+) {
+    let name = names
+        .get(trigger.entity)
+        .map(|name| name.as_str().to_string())
+        .unwrap_or_default();
+    let connected: Vec<&str> = pads.iter().map(|(key, _)| key.0.as_str()).collect();
+    let key = PadAssignments::key(&name, &connected);
+    let taken: Vec<TeamColor> = pads
+        .iter()
+        .filter_map(|(_, plays)| plays)
+        .map(|plays| plays.0)
+        .collect();
+    commands.entity(trigger.entity).insert(PadKey(key.clone()));
+
+    let Some(color) = assignments.pick(&key, &taken) else {
+        info!("Gamepad {} connected; both ships already have one", key);
+        return;
+    };
+    assignments.set(&key, color);
+    commands.entity(trigger.entity).insert(PlaysAs(color));
+    info!("Gamepad {} steers the {:?} ship", key, color);
+}
+
+/// The swap-pads binding (gamepad Select by default) swaps which ship that
+/// pad steers while in the lobby; a pad steering the other ship swaps too.
+fn swap_pad_color(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<Bindings>,
+    rooms: Query<&MatchState>,
+    mut pads: Query<(&Gamepad, &PadKey, &mut PlaysAs)>,
+    mut assignments: ResMut<PadAssignments>,
+) {
+    if !rooms
+        .single()
+        .is_ok_and(|state| *state == MatchState::Lobby)
+    {
+        return;
+    }
+    if !bindings
+        .swap_pads
+        .just_pressed(&keys, pads.iter().map(|(gamepad, ..)| gamepad))
+    {
+        return;
+    }
+    for (_, key, mut plays) in pads.iter_mut() {
+        plays.0 = assignments.swap(&key.0, plays.0);
+        info!("Gamepad {} now steers the {:?} ship", key.0, plays.0);
+    }
+}
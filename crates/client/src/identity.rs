@@ -0,0 +1,30 @@
+use bevy::prelude::*;
+use std::path::Path;
+
+/// The netcode id this client connects, and reconnects, with: `fixed` if
+/// given, else the one kept in `file`, else a fresh random one for this
+/// launch, so clients started side by side never share one. Only a kept id
+/// survives a restart, letting a player get their parked ship back then too.
+pub fn client_id(fixed: Option<u64>, file: Option<&Path>) -> u64 {
+    match (fixed, file) {
+        (Some(id), _) => id,
+        (None, Some(file)) => load_client_id(file),
+        (None, None) => rand::random(),
+    }
+}
+
+/// The id kept in `path`. A missing or unreadable file gets a fresh random
+/// id, saved for next time.
+pub fn load_client_id(path: &Path) -> u64 {
+    let saved = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| text.trim().parse().ok());
+    if let Some(id) = saved {
+        return id;
+    }
+    let id = rand::random::<u64>();
+    if let Err(err) = std::fs::write(path, id.to_string()) {
+        warn!("Could not save client id to {}: {}", path.display(), err);
+    }
+    id
+}
@@ -1,66 +1,206 @@
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use lightyear::connection::host::HostClient;
 use lightyear::prelude::client::input::InputSystems;
 use lightyear::prelude::input::native::{ActionState, InputMarker};
-use lightyear::prelude::Predicted;
-use schizoid_shared::components::Ship;
-use schizoid_shared::protocol::PlayerInput;
+use lightyear::prelude::{Client, Controlled, ControlledBy, MessageSender, Predicted};
+use schizoid_shared::components::{MatchState, Position, Ship, TeamColor};
+use schizoid_shared::protocol::{PlayerInput, ReliableChannel, RequestRematch};
 
-pub struct InputPlugin;
+use crate::bindings::{Bindings, Stick};
+use crate::gamepads::PlaysAs;
+use crate::rebind::{menu_closed, RebindMenu};
+
+pub struct InputPlugin {
+    /// Two players on this machine, one per ship (`--couch`).
+    pub couch: bool,
+}
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
+        app.insert_resource(CouchCoop(self.couch));
         app.add_systems(
             FixedPreUpdate,
             buffer_input.in_set(InputSystems::WriteClientInputs),
         );
+        app.add_systems(Update, request_rematch.run_if(menu_closed));
         app.add_observer(handle_predicted_spawn);
+        app.add_observer(handle_host_ships);
+    }
+}
+
+/// Whether both ships we fly belong to different players on this machine,
+/// rather than one player in solo "Uber" mode.
+#[derive(Resource)]
+struct CouchCoop(bool);
+
+/// First gamepad stick `stick` has pushed past the deadzone
+fn stick<'a>(
+    bindings: &Bindings,
+    gamepads: impl IntoIterator<Item = &'a Gamepad>,
+    stick: &Stick,
+) -> Option<Vec2> {
+    gamepads
+        .into_iter()
+        .find_map(|gamepad| bindings.read_stick(stick, gamepad))
+}
+
+/// Where the cursor is in the world while the fire button is held
+fn mouse_target(
+    mouse: &ButtonInput<MouseButton>,
+    bindings: &Bindings,
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    if !mouse.pressed(bindings.fire_mouse) {
+        return None;
     }
+    let cursor = windows.single().ok()?.cursor_position()?;
+    let (camera, transform) = cameras.single().ok()?;
+    camera.viewport_to_world_2d(transform, cursor).ok()
 }
 
+/// Steer and aim every ship we control. Flying one ship, both key sets and
+/// the stick all drive it, and it aims with the aim stick or the mouse.
+/// Flying both in solo "Uber" mode, Red takes the first key set and stick
+/// and Blue the second ones, and both aim at the mouse. In couch co-op each
+/// ship takes the sticks of the gamepad assigned to it, and the keyboard and
+/// mouse drive the ships without one (split between the key sets when
+/// neither has a gamepad).
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn buffer_input(
-    mut query: Query<&mut ActionState<PlayerInput>, With<InputMarker<PlayerInput>>>,
+    mut query: Query<
+        (&mut ActionState<PlayerInput>, &TeamColor, &Position),
+        With<InputMarker<PlayerInput>>,
+    >,
     keys: Res<ButtonInput<KeyCode>>,
-    gamepads: Query<&Gamepad>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    gamepads: Query<(&Gamepad, Option<&PlaysAs>)>,
+    couch: Res<CouchCoop>,
+    bindings: Res<Bindings>,
+    menu: Res<RebindMenu>,
 ) {
-    if let Ok(mut action_state) = query.single_mut() {
-        let mut dir = Vec2::ZERO;
-
-        // Keyboard WASD + arrows
-        if keys.pressed(KeyCode::KeyW) || keys.pressed(KeyCode::ArrowUp) {
-            dir.y += 1.0;
-        }
-        if keys.pressed(KeyCode::KeyS) || keys.pressed(KeyCode::ArrowDown) {
-            dir.y -= 1.0;
-        }
-        if keys.pressed(KeyCode::KeyA) || keys.pressed(KeyCode::ArrowLeft) {
-            dir.x -= 1.0;
-        }
-        if keys.pressed(KeyCode::KeyD) || keys.pressed(KeyCode::ArrowRight) {
-            dir.x += 1.0;
-        }
-
-        // Gamepad left stick — use whichever gamepad has actual stick input
-        for gamepad in gamepads.iter() {
-            let stick = gamepad.left_stick();
-            if stick.length_squared() > 0.04 {
-                dir = stick;
-                break;
-            }
-        }
+    let target = mouse_target(&mouse, &bindings, &windows, &cameras);
+    let both = query.iter().count() > 1;
+    let all_keys = (bindings.keys.direction(&keys) + bindings.second_keys.direction(&keys))
+        .clamp(Vec2::NEG_ONE, Vec2::ONE);
+    let any_pad = || gamepads.iter().map(|(gamepad, _)| gamepad);
+    let pad_of = |color: TeamColor| {
+        gamepads
+            .iter()
+            .filter(move |(_, plays)| *plays == Some(&PlaysAs(color)))
+            .map(|(gamepad, _)| gamepad)
+    };
+    for (mut action_state, color, pos) in query.iter_mut() {
+        let split_keys = match color {
+            TeamColor::Red => bindings.keys.direction(&keys),
+            TeamColor::Blue => bindings.second_keys.direction(&keys),
+        };
+        let mouse_aim = target.map(|target| target - pos.0);
+        let (keyboard, stick, aim) = if !both {
+            let aim = stick(&bindings, any_pad(), &bindings.aim_stick).or(mouse_aim);
+            (all_keys, stick(&bindings, any_pad(), &bindings.stick), aim)
+        } else if !couch.0 {
+            let own_stick = match color {
+                TeamColor::Red => &bindings.stick,
+                TeamColor::Blue => &bindings.second_stick,
+            };
+            (
+                split_keys,
+                stick(&bindings, any_pad(), own_stick),
+                mouse_aim,
+            )
+        } else {
+            let has_pad = pad_of(*color).next().is_some();
+            let keyboard = match (has_pad, pad_of(color.opposite()).next().is_some()) {
+                (true, _) => Vec2::ZERO,
+                (false, true) => all_keys,
+                (false, false) => split_keys,
+            };
+            let aim = match has_pad {
+                true => stick(&bindings, pad_of(*color), &bindings.aim_stick),
+                false => mouse_aim,
+            };
+            (
+                keyboard,
+                stick(&bindings, pad_of(*color), &bindings.stick),
+                aim,
+            )
+        };
+        // Hold still and fire while rebinding
+        let (mut dir, aim) = if menu.is_open() {
+            (Vec2::ZERO, Vec2::ZERO)
+        } else {
+            (stick.unwrap_or(keyboard), aim.unwrap_or_default())
+        };
 
         // Normalize to prevent diagonal speed boost
         if dir.length_squared() > 1.0 {
             dir = dir.normalize();
         }
 
-        action_state.0 = PlayerInput { direction: dir };
+        action_state.0 = PlayerInput {
+            direction: dir,
+            aim: aim.normalize_or_zero(),
+        };
     }
 }
 
-fn handle_predicted_spawn(trigger: On<Add, (Ship, Predicted)>, mut commands: Commands) {
+/// Ask for a rematch with the rematch binding after a game over
+fn request_rematch(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    matches: Query<&MatchState>,
+    mut senders: Query<&mut MessageSender<RequestRematch>, With<Client>>,
+) {
+    if !bindings.rematch.just_pressed(&keys, gamepads)
+        || !matches
+            .single()
+            .is_ok_and(|state| *state == MatchState::GameOver)
+    {
+        return;
+    }
+
+    for mut sender in senders.iter_mut() {
+        sender.send::<ReliableChannel>(RequestRematch);
+        info!("Requested a rematch");
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn handle_predicted_spawn(
+    trigger: On<Add, (Ship, Predicted, Controlled)>,
+    ships: Query<(), (With<Ship>, With<Predicted>, With<Controlled>)>,
+    mut commands: Commands,
+) {
     let entity = trigger.entity;
+    // Everything is predicted; only the ship we control takes input
+    if ships.get(entity).is_err() {
+        return;
+    }
     commands
         .entity(entity)
         .insert(InputMarker::<PlayerInput>::default());
     info!("Predicted ship spawned, added input marker to {:?}", entity);
 }
+
+/// With a local server there is no prediction: we steer the server's ships
+/// handed to our host client directly.
+fn handle_host_ships(
+    trigger: On<Add, ControlledBy>,
+    ships: Query<&ControlledBy, With<Ship>>,
+    hosts: Query<(), With<HostClient>>,
+    mut commands: Commands,
+) {
+    let Ok(controlled) = ships.get(trigger.entity) else {
+        return;
+    };
+    if hosts.contains(controlled.owner) {
+        commands
+            .entity(trigger.entity)
+            .insert(InputMarker::<PlayerInput>::default());
+    }
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use schizoid_shared::protocol::{LeaderboardMessage, MatchResult};
+
+pub struct LeaderboardPlugin;
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Leaderboard>();
+        app.add_systems(Startup, setup_best_text);
+        app.add_systems(Update, (receive_leaderboard, update_best_text).chain());
+    }
+}
+
+/// The server's best results, as last received.
+#[derive(Resource, Default)]
+pub struct Leaderboard(pub Vec<MatchResult>);
+
+/// Marker for the best-result text
+#[derive(Component)]
+struct BestText;
+
+fn setup_best_text(mut commands: Commands) {
+    commands.spawn((
+        BestText,
+        Text::new(""),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.5, 1.5, 1.5)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            ..default()
+        },
+    ));
+}
+
+fn receive_leaderboard(
+    mut receivers: Query<&mut MessageReceiver<LeaderboardMessage>>,
+    mut leaderboard: ResMut<Leaderboard>,
+) {
+    for mut receiver in receivers.iter_mut() {
+        if let Some(message) = receiver.receive().last() {
+            info!(
+                "Received leaderboard with {} entries",
+                message.entries.len()
+            );
+            leaderboard.0 = message.entries;
+        }
+    }
+}
+
+fn update_best_text(
+    leaderboard: Res<Leaderboard>,
+    mut best_text: Query<&mut Text, With<BestText>>,
+) {
+    if !leaderboard.is_changed() {
+        return;
+    }
+
+    let line = leaderboard
+        .0
+        .first()
+        .map(|best| format!("Best: wave {}, {} pts", best.wave, best.team_score))
+        .unwrap_or_default();
+    for mut text in best_text.iter_mut() {
+        *text = Text::new(line.clone());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod bindings;
+pub mod gamepads;
+pub mod identity;
+pub mod input;
+pub mod leaderboard;
+pub mod lobby;
+pub mod rebind;
+pub mod rendering;
@@ -0,0 +1,164 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use schizoid_shared::components::*;
+use schizoid_shared::protocol::{ChooseColor, JoinRoom, Rejected, ReliableChannel, SetReady};
+
+use crate::bindings::Bindings;
+use crate::rebind::menu_closed;
+
+pub struct LobbyPlugin {
+    pub room: String,
+    pub color: Option<TeamColor>,
+    pub both_ships: bool,
+}
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(RoomChoice {
+            room: self.room.clone(),
+            color: self.color,
+            both_ships: self.both_ships,
+        });
+        app.add_observer(join_on_connect);
+        app.add_systems(Startup, setup_notice_text);
+        app.add_systems(
+            Update,
+            (
+                (toggle_ready, swap_color).run_if(menu_closed),
+                show_rejections,
+                clear_notice,
+            ),
+        );
+    }
+}
+
+/// Room to join once connected, from `--room`, and the color asked for
+/// with `--player` (or both ships, with `--solo` or `--couch`).
+#[derive(Resource)]
+struct RoomChoice {
+    room: String,
+    color: Option<TeamColor>,
+    both_ships: bool,
+}
+
+/// Marker for the text explaining why the server turned a request down
+#[derive(Component)]
+struct NoticeText;
+
+fn setup_notice_text(mut commands: Commands) {
+    commands.spawn((
+        NoticeText,
+        Text::new(""),
+        TextFont {
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.5, 0.5, 0.5)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(34.0),
+            left: Val::Percent(40.0),
+            ..default()
+        },
+    ));
+}
+
+fn join_on_connect(
+    trigger: On<Add, Connected>,
+    mut senders: Query<&mut MessageSender<JoinRoom>, With<Client>>,
+    choice: Res<RoomChoice>,
+) {
+    let Ok(mut sender) = senders.get_mut(trigger.entity) else {
+        return;
+    };
+    sender.send::<ReliableChannel>(JoinRoom {
+        room: choice.room.clone(),
+        color: choice.color,
+        both_ships: choice.both_ships,
+    });
+    info!("Joining room {}", choice.room);
+}
+
+/// This client's entry on its room's roster, if seated.
+fn own_seat(players: &RoomPlayers, local_id: &LocalId) -> Option<RoomPlayer> {
+    players
+        .0
+        .iter()
+        .find(|player| player.client == local_id.0.to_bits())
+        .copied()
+}
+
+/// The ready binding flips this client's ready flag in the lobby
+fn toggle_ready(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    rooms: Query<(&MatchState, &RoomPlayers)>,
+    mut clients: Query<(&LocalId, &mut MessageSender<SetReady>), With<Client>>,
+) {
+    let pressed = bindings.ready.just_pressed(&keys, gamepads);
+    let (Ok((state, players)), Ok((local_id, mut sender))) = (rooms.single(), clients.single_mut())
+    else {
+        return;
+    };
+    if !pressed || matches!(state, MatchState::Playing | MatchState::GameOver) {
+        return;
+    }
+    if let Some(seat) = own_seat(players, local_id) {
+        sender.send::<ReliableChannel>(SetReady(!seat.ready));
+    }
+}
+
+/// The swap-color binding asks for the other color while in the lobby
+fn swap_color(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    bindings: Res<Bindings>,
+    rooms: Query<(&MatchState, &RoomPlayers)>,
+    mut clients: Query<(&LocalId, &mut MessageSender<ChooseColor>), With<Client>>,
+) {
+    let pressed = bindings.swap_color.just_pressed(&keys, gamepads);
+    let (Ok((state, players)), Ok((local_id, mut sender))) = (rooms.single(), clients.single_mut())
+    else {
+        return;
+    };
+    if !pressed || *state != MatchState::Lobby {
+        return;
+    }
+    if let Some(seat) = own_seat(players, local_id) {
+        sender.send::<ReliableChannel>(ChooseColor(seat.color.opposite()));
+    }
+}
+
+fn show_rejections(
+    mut receivers: Query<&mut MessageReceiver<Rejected>, With<Client>>,
+    mut notice_text: Query<&mut Text, With<NoticeText>>,
+) {
+    for mut receiver in receivers.iter_mut() {
+        let Some(rejection) = receiver.receive().last() else {
+            continue;
+        };
+        warn!("Server turned down our request: {:?}", rejection);
+        let line = match rejection {
+            Rejected::RoomFull(room) => format!("Room {room} is full"),
+            Rejected::ColorTaken(color) => format!("{color:?} is already taken"),
+            Rejected::MatchUnderway => "Colors are locked once the match starts".to_string(),
+        };
+        for mut text in notice_text.iter_mut() {
+            *text = Text::new(line.clone());
+        }
+    }
+}
+
+/// Drop the notice once the roster changes, e.g. after a successful join
+fn clear_notice(
+    rooms: Query<(), Changed<RoomPlayers>>,
+    mut notice_text: Query<&mut Text, With<NoticeText>>,
+) {
+    if rooms.is_empty() {
+        return;
+    }
+    for mut text in notice_text.iter_mut() {
+        *text = Text::new("");
+    }
+}
@@ -1,12 +1,21 @@
 use bevy::prelude::*;
 use clap::Parser;
 use lightyear::prelude::client::*;
+use lightyear::prelude::server::ServerPlugins;
 use lightyear::prelude::*;
-use schizoid_shared::{SharedPlugin, SERVER_PORT, TICK_DURATION};
+use schizoid_client::{bindings, gamepads, identity, input, leaderboard, lobby, rebind, rendering};
+use schizoid_server::game::{ServerGamePlugin, ServerTransport};
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::archetypes::EnemyRegistry;
+use schizoid_shared::components::{ShipHandling, TeamColor};
+use schizoid_shared::director::DirectorConfig;
+use schizoid_shared::waves::WaveTable;
+use schizoid_shared::{
+    parse_tick_rate, SharedPlugin, SimulationPlugin, SERVER_PORT, TICK_DURATION,
+};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-
-mod input;
-mod rendering;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "schizoid-client")]
@@ -18,11 +27,88 @@ struct Args {
     /// Server port
     #[arg(short, long, default_value_t = SERVER_PORT)]
     port: u16,
+
+    /// Simulation tick rate in Hz (must match the server)
+    #[arg(long, value_parser = parse_tick_rate)]
+    tick_rate: Option<f64>,
+
+    /// Room to join; players who pick the same name share a match
+    #[arg(long, default_value = "default")]
+    room: String,
+
+    /// Team color to fly (red or blue); the server turns the join down if
+    /// it's taken. Without it, any free color
+    #[arg(long)]
+    player: Option<TeamColor>,
+
+    /// Fly both ships yourself: Red on WASD / left stick, Blue on arrows /
+    /// right stick
+    #[arg(long)]
+    solo: bool,
+
+    /// Two players on this machine, one per ship: each gamepad steers the
+    /// ship it's assigned to, the keyboard any ship without one
+    #[arg(long, conflicts_with = "solo")]
+    couch: bool,
+
+    /// File `--couch` keeps which gamepad flies which ship in
+    #[arg(long, default_value = "gamepads.json", requires = "couch")]
+    gamepads: PathBuf,
+
+    /// Run the server inside this process instead of connecting to one
+    #[arg(long)]
+    local: bool,
+
+    /// Key and gamepad bindings, created with the defaults if missing and
+    /// reloaded when edited. F1 in game rebinds keys too
+    #[arg(long, default_value = "bindings.ron")]
+    bindings: PathBuf,
+
+    /// Id to connect with, so the server knows you when you reconnect.
+    /// Without it, a random one for this launch
+    #[arg(long, conflicts_with_all = ["local", "id_file"])]
+    client_id: Option<u64>,
+
+    /// Keep the client id in this file, so a restarted client gets its
+    /// parked ship back too. Each client needs its own file
+    #[arg(long, conflicts_with = "local")]
+    id_file: Option<PathBuf>,
+
+    /// Give ships momentum in `--local` matches: they accelerate, coast and
+    /// slide along walls
+    #[arg(long, requires = "local")]
+    momentum: bool,
+
+    /// Enemy archetypes to play with (the server and every client need the
+    /// same file); the built-in enemies if missing
+    #[arg(long, default_value = "assets/enemies.ron")]
+    enemies: PathBuf,
+
+    /// Keep waves at their base difficulty instead of adapting them to how
+    /// the team is doing
+    #[arg(long, requires = "local")]
+    no_director: bool,
+
+    /// Authored waves the `--local` server plays before the procedural ones
+    /// take over; all procedural if missing
+    #[arg(long, default_value = "assets/waves.ron", requires = "local")]
+    waves: PathBuf,
+
+    /// File the `--local` server keeps its leaderboard in
+    #[arg(long, default_value = "leaderboard.json", requires = "local")]
+    leaderboard: PathBuf,
 }
 
 #[derive(Resource)]
 struct ServerAddr(SocketAddr);
 
+/// The netcode id this client connects, and reconnects, with.
+#[derive(Resource, Clone, Copy)]
+struct ClientId(u64);
+
+/// Seconds between attempts to get back to a server we lost.
+const RECONNECT_DELAY: f32 = 2.0;
+
 fn main() {
     let args = Args::parse();
     let server_addr = SocketAddr::new(
@@ -31,6 +117,10 @@ fn main() {
             .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST)),
         args.port,
     );
+    let tick_duration = args
+        .tick_rate
+        .map(|hz| Duration::from_secs_f64(1.0 / hz))
+        .unwrap_or(TICK_DURATION);
 
     let mut app = App::new();
 
@@ -44,49 +134,89 @@ fn main() {
     }));
 
     // Lightyear client
-    app.add_plugins(lightyear::prelude::client::ClientPlugins {
-        tick_duration: TICK_DURATION,
-    });
+    app.add_plugins(lightyear::prelude::client::ClientPlugins { tick_duration });
 
     // Game
     app.add_plugins(SharedPlugin);
-    app.add_plugins(input::InputPlugin);
+    let registry = EnemyRegistry::load(&args.enemies);
+    if args.local {
+        app.insert_resource(WaveTable::load(&args.waves, &registry));
+        // Host-server: the authoritative simulation runs on the same
+        // entities we render, so there is nothing to predict
+        app.add_plugins(ServerPlugins { tick_duration });
+        app.add_plugins(ServerGamePlugin {
+            transport: ServerTransport::Local,
+            spawn_waves: true,
+            seed: None,
+            leaderboard: Some(args.leaderboard),
+            rules: MatchRules {
+                players: 1,
+                handling: match args.momentum {
+                    true => ShipHandling::Momentum(default()),
+                    false => ShipHandling::Direct,
+                },
+                director: DirectorConfig {
+                    enabled: !args.no_director,
+                    ..default()
+                },
+                ..default()
+            },
+        });
+        app.add_systems(PostStartup, setup_local_connection);
+    } else {
+        // Client-side prediction: run shared game systems on predicted entities
+        app.add_plugins(SimulationPlugin);
+        app.insert_resource(ServerAddr(server_addr));
+        app.insert_resource(ClientId(identity::client_id(
+            args.client_id,
+            args.id_file.as_deref(),
+        )));
+        app.add_systems(Startup, setup_connection);
+        app.add_systems(Update, reconnect);
+    }
+    app.insert_resource(registry);
+    app.add_plugins(bindings::BindingsPlugin {
+        path: args.bindings,
+    });
+    app.add_plugins(rebind::RebindPlugin);
+    app.add_plugins(input::InputPlugin { couch: args.couch });
+    if args.couch {
+        app.add_plugins(gamepads::GamepadAssignmentPlugin {
+            path: args.gamepads,
+        });
+    }
     app.add_plugins(rendering::RenderingPlugin);
+    app.add_plugins(leaderboard::LeaderboardPlugin);
+    app.add_plugins(lobby::LobbyPlugin {
+        room: args.room,
+        color: args.player,
+        both_ships: args.solo || args.couch,
+    });
 
-    // Client-side prediction: run shared game systems on predicted entities
-    app.add_systems(
-        FixedUpdate,
-        (
-            schizoid_shared::systems::ship_movement,
-            schizoid_shared::systems::enemy_movement,
-            schizoid_shared::systems::chaser_ai,
-            schizoid_shared::systems::collision_system,
-            schizoid_shared::systems::respawn_system,
-        )
-            .chain(),
-    );
-
-    // Client connection setup
-    app.insert_resource(ServerAddr(server_addr));
-    app.add_systems(Startup, setup_connection);
-
-    info!("Starting client, connecting to {}", server_addr);
+    if args.local {
+        info!("Starting client with a local server");
+    } else {
+        info!("Starting client, connecting to {}", server_addr);
+    }
     app.run();
 }
 
-fn setup_connection(mut commands: Commands, server_addr: Res<ServerAddr>) {
+/// Connect as the host client of the in-process server (started during
+/// `Startup`); no sockets involved.
+fn setup_local_connection(mut commands: Commands, server: Single<Entity, With<Server>>) {
+    let client = commands
+        .spawn((Client::default(), LinkOf { server: *server }))
+        .id();
+    commands.trigger(Connect { entity: client });
+    info!("Connecting to the local server");
+}
+
+fn setup_connection(mut commands: Commands, server_addr: Res<ServerAddr>, id: Res<ClientId>) {
     let client_addr = SocketAddr::new(
         IpAddr::V4(Ipv4Addr::UNSPECIFIED),
         0, // Let OS assign port
     );
 
-    let auth = lightyear::prelude::Authentication::Manual {
-        server_addr: server_addr.0,
-        client_id: rand::random::<u64>(),
-        private_key: [0u8; 32], // Default key matches server default
-        protocol_id: 0,         // Default protocol matches server default
-    };
-
     let client = commands
         .spawn((
             Client::default(),
@@ -95,7 +225,7 @@ fn setup_connection(mut commands: Commands, server_addr: Res<ServerAddr>) {
             Link::new(None),
             ReplicationReceiver::default(),
             PredictionManager::default(),
-            NetcodeClient::new(auth, NetcodeConfig::default()).unwrap(),
+            netcode_client(server_addr.0, *id),
             UdpIo::default(),
         ))
         .id();
@@ -104,3 +234,40 @@ fn setup_connection(mut commands: Commands, server_addr: Res<ServerAddr>) {
 
     info!("Connecting to server at {}", server_addr.0);
 }
+
+/// A netcode client with a fresh connect token for `id`.
+fn netcode_client(server_addr: SocketAddr, id: ClientId) -> NetcodeClient {
+    let auth = lightyear::prelude::Authentication::Manual {
+        server_addr,
+        client_id: id.0,
+        private_key: [0u8; 32], // Default key matches server default
+        protocol_id: 0,         // Default protocol matches server default
+    };
+    NetcodeClient::new(auth, NetcodeConfig::default()).unwrap()
+}
+
+/// Keep trying to get back to the server after losing it, under the same
+/// id, so the server hands our parked ships back.
+fn reconnect(
+    clients: Query<Entity, (With<NetcodeClient>, With<Disconnected>)>,
+    server_addr: Res<ServerAddr>,
+    id: Res<ClientId>,
+    time: Res<Time>,
+    mut waited: Local<f32>,
+    mut commands: Commands,
+) {
+    let Ok(client) = clients.single() else {
+        *waited = 0.0;
+        return;
+    };
+    *waited += time.delta_secs();
+    if *waited < RECONNECT_DELAY {
+        return;
+    }
+    *waited = 0.0;
+    commands
+        .entity(client)
+        .insert(netcode_client(server_addr.0, *id));
+    commands.trigger(Connect { entity: client });
+    info!("Reconnecting to server at {}", server_addr.0);
+}
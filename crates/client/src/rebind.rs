@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+
+use crate::bindings::{Bindings, BindingsFile, Buttons};
+
+/// In-game rebinding screen. F1 opens and closes it, Up/Down pick an action,
+/// Enter waits for the key (or gamepad button) to bind to it, Escape backs
+/// out. Changes are saved to the bindings file straight away.
+pub struct RebindPlugin;
+
+impl Plugin for RebindPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RebindMenu>();
+        app.add_systems(Startup, setup_menu_text);
+        app.add_systems(
+            Update,
+            (navigate_menu, capture_binding, update_menu_text).chain(),
+        );
+    }
+}
+
+/// Every action that can be rebound, in the order the screen lists them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    SecondUp,
+    SecondDown,
+    SecondLeft,
+    SecondRight,
+    Ready,
+    SwapColor,
+    Rematch,
+    SwapPads,
+}
+
+const ACTIONS: [Action; 12] = [
+    Action::Up,
+    Action::Down,
+    Action::Left,
+    Action::Right,
+    Action::SecondUp,
+    Action::SecondDown,
+    Action::SecondLeft,
+    Action::SecondRight,
+    Action::Ready,
+    Action::SwapColor,
+    Action::Rematch,
+    Action::SwapPads,
+];
+
+impl Action {
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::SecondUp => "Second ship up",
+            Action::SecondDown => "Second ship down",
+            Action::SecondLeft => "Second ship left",
+            Action::SecondRight => "Second ship right",
+            Action::Ready => "Ready",
+            Action::SwapColor => "Swap color",
+            Action::Rematch => "Rematch",
+            Action::SwapPads => "Swap gamepads",
+        }
+    }
+
+    /// The keys steering a ship, for the movement actions.
+    fn ship_keys(self, bindings: &mut Bindings) -> Option<&mut Vec<KeyCode>> {
+        Some(match self {
+            Action::Up => &mut bindings.keys.up,
+            Action::Down => &mut bindings.keys.down,
+            Action::Left => &mut bindings.keys.left,
+            Action::Right => &mut bindings.keys.right,
+            Action::SecondUp => &mut bindings.second_keys.up,
+            Action::SecondDown => &mut bindings.second_keys.down,
+            Action::SecondLeft => &mut bindings.second_keys.left,
+            Action::SecondRight => &mut bindings.second_keys.right,
+            _ => return None,
+        })
+    }
+
+    /// The keys and buttons of the other actions.
+    fn buttons(self, bindings: &mut Bindings) -> Option<&mut Buttons> {
+        match self {
+            Action::Ready => Some(&mut bindings.ready),
+            Action::SwapColor => Some(&mut bindings.swap_color),
+            Action::Rematch => Some(&mut bindings.rematch),
+            Action::SwapPads => Some(&mut bindings.swap_pads),
+            _ => None,
+        }
+    }
+
+    /// What's bound to the action, e.g. "KeyW" or "Enter, Start".
+    fn describe(self, bindings: &mut Bindings) -> String {
+        if let Some(buttons) = self.buttons(bindings) {
+            return buttons.describe();
+        }
+        let names: Vec<String> = self
+            .ship_keys(bindings)
+            .map(|keys| keys.iter().map(|key| format!("{key:?}")).collect())
+            .unwrap_or_default();
+        if names.is_empty() {
+            "-".to_string()
+        } else {
+            names.join(", ")
+        }
+    }
+}
+
+/// State of the rebinding screen.
+#[derive(Resource, Default)]
+pub struct RebindMenu {
+    open: bool,
+    /// Index into `ACTIONS`.
+    selected: usize,
+    /// Waiting for the next key or button press to bind to the selection.
+    listening: bool,
+}
+
+impl RebindMenu {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+}
+
+/// Run condition for gameplay controls, which the rebinding screen takes over.
+pub fn menu_closed(menu: Res<RebindMenu>) -> bool {
+    !menu.open
+}
+
+/// Marker for the rebinding screen's text
+#[derive(Component)]
+struct MenuText;
+
+fn setup_menu_text(mut commands: Commands) {
+    commands.spawn((
+        MenuText,
+        Text::new(""),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.5, 1.5, 1.5)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(20.0),
+            left: Val::Percent(30.0),
+            padding: UiRect::all(Val::Px(16.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+fn navigate_menu(keys: Res<ButtonInput<KeyCode>>, mut menu: ResMut<RebindMenu>) {
+    if menu.listening {
+        return;
+    }
+    if keys.just_pressed(KeyCode::F1) {
+        menu.open = !menu.open;
+        return;
+    }
+    if !menu.open {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        menu.open = false;
+    } else if keys.just_pressed(KeyCode::ArrowUp) {
+        menu.selected = (menu.selected + ACTIONS.len() - 1) % ACTIONS.len();
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        menu.selected = (menu.selected + 1) % ACTIONS.len();
+    } else if keys.just_pressed(KeyCode::Enter) {
+        // Start listening next frame, so this Enter isn't the new binding
+        menu.listening = true;
+    }
+}
+
+/// Bind the first key or gamepad button pressed while listening to the
+/// selected action, replacing what it had. Movement only takes keys; sticks
+/// are set in the bindings file.
+fn capture_binding(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut menu: ResMut<RebindMenu>,
+    mut bindings: ResMut<Bindings>,
+    mut file: ResMut<BindingsFile>,
+) {
+    if !menu.listening || menu.is_changed() {
+        return;
+    }
+    if keys.just_pressed(KeyCode::Escape) {
+        menu.listening = false;
+        return;
+    }
+    let key = keys.get_just_pressed().next().copied();
+    let button = gamepads
+        .iter()
+        .find_map(|gamepad| gamepad.get_just_pressed().next().copied());
+    let action = ACTIONS[menu.selected];
+    let bound = match (key, button) {
+        (Some(key), _) => {
+            if let Some(keys) = action.ship_keys(&mut bindings) {
+                *keys = vec![key];
+            } else if let Some(buttons) = action.buttons(&mut bindings) {
+                buttons.keys = vec![key];
+            }
+            true
+        }
+        (None, Some(button)) => match action.buttons(&mut bindings) {
+            Some(buttons) => {
+                buttons.buttons = vec![button];
+                true
+            }
+            None => false,
+        },
+        (None, None) => false,
+    };
+    if bound {
+        menu.listening = false;
+        file.save(&bindings);
+        info!("Rebound {}", action.label());
+    }
+}
+
+fn update_menu_text(
+    menu: Res<RebindMenu>,
+    bindings: Res<Bindings>,
+    mut menu_text: Query<(&mut Text, &mut Visibility), With<MenuText>>,
+) {
+    if !menu.is_changed() && !bindings.is_changed() {
+        return;
+    }
+    let Ok((mut text, mut visibility)) = menu_text.single_mut() else {
+        return;
+    };
+    *visibility = if menu.open {
+        Visibility::Visible
+    } else {
+        Visibility::Hidden
+    };
+    // The accessors hand out the bindings mutably, for capture_binding
+    let mut bindings = bindings.clone();
+    let mut lines = vec!["Controls (Up/Down, Enter to rebind, Esc to close)".to_string()];
+    for (index, action) in ACTIONS.iter().enumerate() {
+        let marker = if index == menu.selected { ">" } else { " " };
+        let binding = if index == menu.selected && menu.listening {
+            "press a key or button...".to_string()
+        } else {
+            action.describe(&mut bindings)
+        };
+        lines.push(format!("{marker} {:<18} {binding}", action.label()));
+    }
+    *text = Text::new(lines.join("\n"));
+}
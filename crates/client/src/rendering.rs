@@ -1,8 +1,12 @@
 use bevy::core_pipeline::tonemapping::Tonemapping;
 use bevy::post_process::bloom::Bloom;
 use bevy::prelude::*;
+use schizoid_shared::archetypes::{EnemyRegistry, Shape};
 use schizoid_shared::components::*;
 
+use crate::bindings::{Bindings, Buttons};
+use crate::rebind::Action;
+
 pub struct RenderingPlugin;
 
 impl Plugin for RenderingPlugin {
@@ -10,10 +14,19 @@ impl Plugin for RenderingPlugin {
         app.add_systems(Startup, setup_camera);
         app.add_systems(Startup, setup_arena);
         app.add_systems(Update, sync_transforms);
+        app.add_systems(Update, sync_facing);
         app.add_systems(Update, spawn_ship_visuals);
         app.add_systems(Update, spawn_enemy_visuals);
+        app.add_systems(Update, recolor_enemies);
+        app.add_systems(Update, spawn_powerup_visuals);
+        app.add_systems(Update, spawn_projectile_visuals);
+        app.add_systems(Update, update_rope_beam);
         app.add_systems(Update, update_hud);
+        app.add_systems(Update, update_score_hud);
+        app.add_systems(Update, update_boss_bar);
+        app.add_systems(Update, update_status);
         app.add_systems(Update, blink_invulnerable);
+        app.add_systems(Update, blink_spawn_warnings);
     }
 }
 
@@ -25,10 +38,29 @@ struct HasVisuals;
 #[derive(Component)]
 struct WaveText;
 
+/// Marker for the per-ship score text
+#[derive(Component)]
+struct ScoreText;
+
+/// Marker for the boss health bar, shown while a boss is alive
+#[derive(Component)]
+struct BossBar;
+
+/// Marker for the part of the boss health bar still left
+#[derive(Component)]
+struct BossBarFill;
+
 /// Marker for connection status text
 #[derive(Component)]
 struct StatusText;
 
+/// Marker for the electric rope beam drawn between the two ships
+#[derive(Component)]
+struct RopeBeam;
+
+/// Rope glow: both team colors at once, bright enough to bloom hard
+const ROPE_COLOR: Color = Color::srgb(6.0, 2.0, 6.0);
+
 fn setup_camera(mut commands: Commands) {
     commands.spawn((
         Camera2d,
@@ -79,6 +111,15 @@ fn setup_arena(
         Transform::from_xyz(bounds.half_width, 0.0, 0.0),
     ));
 
+    // Electric rope beam: a unit-length bar stretched between the ships
+    commands.spawn((
+        RopeBeam,
+        Mesh2d(meshes.add(Rectangle::new(1.0, ROPE_HALF_WIDTH * 2.0))),
+        MeshMaterial2d(materials.add(ROPE_COLOR)),
+        Transform::from_xyz(0.0, 0.0, 0.8),
+        Visibility::Hidden,
+    ));
+
     // HUD - Wave counter
     commands.spawn((
         WaveText,
@@ -96,6 +137,48 @@ fn setup_arena(
         },
     ));
 
+    // HUD - Scores, beside the wave counter
+    commands.spawn((
+        ScoreText,
+        Text::new(""),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        TextColor(Color::srgb(2.0, 2.0, 2.0)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Percent(55.0),
+            ..default()
+        },
+    ));
+
+    // HUD - Boss health, under the wave counter
+    commands
+        .spawn((
+            BossBar,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(44.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                height: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
+            Visibility::Hidden,
+        ))
+        .with_child((
+            BossBarFill,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(TeamColor::Red.emissive()),
+        ));
+
     // Connection status
     commands.spawn((
         StatusText,
@@ -122,6 +205,18 @@ fn sync_transforms(mut query: Query<(&Position, &mut Transform), Changed<Positio
     }
 }
 
+/// Turn meshes to face where their `Facing` points
+fn sync_facing(mut query: Query<(&Facing, &mut Transform), Changed<Facing>>) {
+    for (facing, mut transform) in query.iter_mut() {
+        transform.rotation = facing_rotation(facing);
+    }
+}
+
+/// Meshes are built pointing up (+Y); `Facing` is measured from +X
+fn facing_rotation(facing: &Facing) -> Quat {
+    Quat::from_rotation_z(facing.0 - std::f32::consts::FRAC_PI_2)
+}
+
 /// Spawn visuals for new ship entities
 #[allow(clippy::type_complexity)]
 fn spawn_ship_visuals(
@@ -140,42 +235,279 @@ fn spawn_ship_visuals(
     }
 }
 
-/// Spawn visuals for new enemy entities
+/// Spawn visuals for new enemy entities, in the shape their archetype asks
+/// for or their behaviour's usual one
 #[allow(clippy::type_complexity)]
 fn spawn_enemy_visuals(
     enemies: Query<
-        (Entity, &TeamColor, &EnemyType, &Radius, &Position),
+        (
+            Entity,
+            &TeamColor,
+            &EnemyType,
+            Option<&Archetype>,
+            Option<&Facing>,
+            &Radius,
+            &Position,
+        ),
         (Without<Ship>, Without<HasVisuals>),
     >,
+    registry: Res<EnemyRegistry>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    for (entity, color, enemy_type, radius, pos) in enemies.iter() {
-        let mesh = match enemy_type {
-            EnemyType::Drifter => meshes.add(Circle::new(radius.0)),
-            EnemyType::Chaser => meshes.add(RegularPolygon::new(radius.0, 3)),
-            EnemyType::Orbiter => meshes.add(Annulus::new(radius.0 * 0.6, radius.0)),
+    for (entity, color, enemy_type, archetype, facing, radius, pos) in enemies.iter() {
+        let shape = archetype
+            .and_then(|archetype| registry.of(archetype))
+            .and_then(|archetype| archetype.shape);
+        let mesh = match (shape, enemy_type) {
+            (Some(Shape::Circle), _) => meshes.add(Circle::new(radius.0)),
+            (Some(Shape::Ring), _) => meshes.add(Annulus::new(radius.0 * 0.6, radius.0)),
+            (Some(Shape::Polygon(sides)), _) => meshes.add(RegularPolygon::new(radius.0, sides)),
+            (None, EnemyType::Drifter) => meshes.add(Circle::new(radius.0)),
+            (None, EnemyType::Chaser) => meshes.add(RegularPolygon::new(radius.0, 3)),
+            (None, EnemyType::Orbiter) => meshes.add(Annulus::new(radius.0 * 0.6, radius.0)),
+            (None, EnemyType::Splitter) => meshes.add(RegularPolygon::new(radius.0, 6)),
+            (None, EnemyType::Shielder) => meshes.add(Circle::new(radius.0 * 0.6)),
+            (None, EnemyType::Boss) => meshes.add(RegularPolygon::new(radius.0, 8)),
         };
 
         commands.entity(entity).insert((
             HasVisuals,
             Mesh2d(mesh),
             MeshMaterial2d(materials.add(color.emissive_dim())),
+            // A shielder may already face somewhere by now, and `sync_facing`
+            // only catches the turns after this
+            Transform::from_xyz(pos.0.x, pos.0.y, 0.5)
+                .with_rotation(facing.map_or(Quat::IDENTITY, facing_rotation)),
+        ));
+
+        if *enemy_type == EnemyType::Shielder {
+            // Bright half-disc on the facing side; rotates with the parent
+            commands.entity(entity).with_child((
+                Mesh2d(meshes.add(CircularSector::new(radius.0, std::f32::consts::FRAC_PI_2))),
+                MeshMaterial2d(materials.add(color.emissive())),
+                Transform::from_xyz(0.0, 0.0, 0.1),
+            ));
+        }
+    }
+}
+
+/// Repaint enemies whose color changed after their visuals were spawned, as
+/// a boss's does between phases
+#[allow(clippy::type_complexity)]
+fn recolor_enemies(
+    enemies: Query<
+        (&TeamColor, &MeshMaterial2d<ColorMaterial>),
+        (With<EnemyType>, Without<Ship>, Changed<TeamColor>),
+    >,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (color, material) in enemies.iter() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.color = color.emissive_dim();
+        }
+    }
+}
+
+/// Spawn visuals for new power-up pickups
+fn spawn_powerup_visuals(
+    pickups: Query<(Entity, &PowerUp, &Radius, &Position), Without<HasVisuals>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, powerup, radius, pos) in pickups.iter() {
+        let mesh = match powerup {
+            PowerUp::ElectricRope => meshes.add(Rhombus::new(radius.0 * 2.0, radius.0 * 2.0)),
+        };
+
+        commands.entity(entity).insert((
+            HasVisuals,
+            Mesh2d(mesh),
+            MeshMaterial2d(materials.add(ROPE_COLOR)),
             Transform::from_xyz(pos.0.x, pos.0.y, 0.5),
         ));
     }
 }
 
+/// Spawn visuals for new shots, bright in the color they can kill
+#[allow(clippy::type_complexity)]
+fn spawn_projectile_visuals(
+    shots: Query<(Entity, &TeamColor, &Radius, &Position), (With<Projectile>, Without<HasVisuals>)>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, color, radius, pos) in shots.iter() {
+        commands.entity(entity).insert((
+            HasVisuals,
+            Mesh2d(meshes.add(Circle::new(radius.0))),
+            MeshMaterial2d(materials.add(color.emissive())),
+            Transform::from_xyz(pos.0.x, pos.0.y, 0.7),
+        ));
+    }
+}
+
+/// Stretch the rope beam between the Red and Blue ships while a rope is
+/// active, from our predicted ship to the partner's interpolated one
+fn update_rope_beam(
+    ropes: Query<(), With<ElectricRope>>,
+    ships: Query<(&Position, &TeamColor, &Health), With<Ship>>,
+    mut beam: Query<(&mut Transform, &mut Visibility), With<RopeBeam>>,
+) {
+    let Ok((mut transform, mut vis)) = beam.single_mut() else {
+        return;
+    };
+
+    let endpoint = |color: TeamColor| {
+        ships
+            .iter()
+            .find(|(_, ship_color, health)| **ship_color == color && health.alive)
+            .map(|(pos, _, _)| pos.0)
+    };
+
+    match (
+        ropes.is_empty(),
+        endpoint(TeamColor::Red),
+        endpoint(TeamColor::Blue),
+    ) {
+        (false, Some(red), Some(blue)) => {
+            let span = blue - red;
+            let mid = (red + blue) / 2.0;
+            transform.translation.x = mid.x;
+            transform.translation.y = mid.y;
+            transform.rotation = Quat::from_rotation_z(span.to_angle());
+            transform.scale.x = span.length();
+            *vis = Visibility::Visible;
+        }
+        _ => *vis = Visibility::Hidden,
+    }
+}
+
 /// Update wave counter HUD
-fn update_hud(wave: Res<WaveState>, mut wave_text: Query<&mut Text, With<WaveText>>) {
-    if wave.is_changed() {
-        for mut text in wave_text.iter_mut() {
-            *text = Text::new(format!("Wave {}", wave.current_wave));
+fn update_hud(
+    waves: Query<&WaveState, Changed<WaveState>>,
+    mut wave_text: Query<&mut Text, With<WaveText>>,
+) {
+    let Ok(wave) = waves.single() else {
+        return;
+    };
+    for mut text in wave_text.iter_mut() {
+        *text = Text::new(format!("Wave {}", wave.current_wave));
+    }
+}
+
+/// Update per-ship score HUD
+fn update_score_hud(
+    ships: Query<(&TeamColor, &Score), With<Ship>>,
+    changed: Query<(), (With<Ship>, Changed<Score>)>,
+    mut score_text: Query<&mut Text, With<ScoreText>>,
+) {
+    if changed.is_empty() {
+        return;
+    }
+
+    let mut scores: Vec<_> = ships.iter().collect();
+    scores.sort_by_key(|(color, _)| **color != TeamColor::Red);
+    let line = scores
+        .iter()
+        .map(|(color, score)| match score.combo {
+            0 => format!("{:?} {}", color, score.points),
+            _ => format!("{:?} {} x{}", color, score.points, score.multiplier()),
+        })
+        .collect::<Vec<_>>()
+        .join("   ");
+
+    for mut text in score_text.iter_mut() {
+        *text = Text::new(line.clone());
+    }
+}
+
+/// Fill the boss health bar with what the boss has left, in its current
+/// color, and hide it when no boss is alive
+fn update_boss_bar(
+    bosses: Query<(&EnemyHealth, &TeamColor), With<BossState>>,
+    mut bar: Query<&mut Visibility, With<BossBar>>,
+    mut fill: Query<(&mut Node, &mut BackgroundColor), With<BossBarFill>>,
+) {
+    let (Ok(mut vis), Ok((mut node, mut background))) = (bar.single_mut(), fill.single_mut())
+    else {
+        return;
+    };
+
+    match bosses.iter().next() {
+        Some((health, color)) => {
+            node.width = Val::Percent(health.fraction() * 100.0);
+            background.0 = color.emissive();
+            vis.set_if_neq(Visibility::Visible);
+        }
+        None => {
+            vis.set_if_neq(Visibility::Hidden);
         }
     }
 }
 
+/// Show where the match is up to, how many lives are left, and who dropped
+/// out, with whatever the lobby and rematch actions are bound to
+#[allow(clippy::type_complexity)]
+fn update_status(
+    matches: Query<(Ref<MatchState>, Ref<TeamLives>, &RoomName, Ref<RoomPlayers>)>,
+    bindings: Res<Bindings>,
+    mut status_text: Query<&mut Text, With<StatusText>>,
+) {
+    let Ok((state, lives, name, players)) = matches.single() else {
+        return;
+    };
+    let changed =
+        state.is_changed() || lives.is_changed() || players.is_changed() || bindings.is_changed();
+    if !changed {
+        return;
+    }
+    let hint =
+        |action: Action, buttons: &Buttons| format!("{}: {}", action.label(), buttons.describe());
+
+    let line = match *state {
+        MatchState::Lobby => {
+            let roster = players
+                .0
+                .iter()
+                .map(|player| match player.ready {
+                    true => format!("{:?} ready", player.color),
+                    false => format!("{:?} waiting", player.color),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "Room {}: {} - {} - {}",
+                name.0,
+                roster,
+                hint(Action::Ready, &bindings.ready),
+                hint(Action::SwapColor, &bindings.swap_color)
+            )
+        }
+        MatchState::Countdown { remaining } => format!("Get ready: {}", remaining.ceil()),
+        MatchState::Playing => {
+            let dropped: Vec<_> = players
+                .0
+                .iter()
+                .filter(|player| !player.connected)
+                .map(|player| format!("{:?} dropped out", player.color))
+                .collect();
+            match dropped.is_empty() {
+                true => format!("Lives: {}", lives.0),
+                false => format!("Lives: {} - {}", lives.0, dropped.join(", ")),
+            }
+        }
+        MatchState::GameOver => {
+            format!("Game over - {}", hint(Action::Rematch, &bindings.rematch))
+        }
+    };
+    for mut text in status_text.iter_mut() {
+        *text = Text::new(line.clone());
+    }
+}
+
 /// Blink ships that are invulnerable
 fn blink_invulnerable(mut ships: Query<(&Health, &mut Visibility), With<Ship>>, time: Res<Time>) {
     for (health, mut vis) in ships.iter_mut() {
@@ -194,3 +526,33 @@ fn blink_invulnerable(mut ships: Query<(&Health, &mut Visibility), With<Ship>>,
         }
     }
 }
+
+/// Blink enemies that are still telegraphing their arrival, faster as it
+/// nears, so players see where they'll appear
+#[allow(clippy::type_complexity)]
+fn blink_spawn_warnings(
+    mut enemies: Query<
+        (Option<&SpawnWarning>, &mut Visibility),
+        (With<EnemyType>, With<HasVisuals>),
+    >,
+    time: Res<Time>,
+) {
+    for (warning, mut vis) in enemies.iter_mut() {
+        let visible = match warning {
+            Some(warning) => {
+                let rate = if warning.0 > SPAWN_TELEGRAPH / 2.0 {
+                    6.0
+                } else {
+                    12.0
+                };
+                ((time.elapsed_secs() * rate) as u32).is_multiple_of(2)
+            }
+            None => true,
+        };
+        vis.set_if_neq(if visible {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
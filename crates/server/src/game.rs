@@ -2,62 +2,111 @@ use bevy::prelude::*;
 use lightyear::prelude::server::*;
 use lightyear::prelude::*;
 use schizoid_shared::components::*;
-use schizoid_shared::enemies::spawn_wave;
+use schizoid_shared::protocol::{LeaderboardMessage, ReliableChannel};
+use schizoid_shared::waves::WavesPlugin;
+use schizoid_shared::{AuthorityPlugin, SimulationPlugin, SimulationSystems};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+
+use crate::leaderboard::Leaderboard;
+use crate::lifecycle::{self, MatchRules};
+use crate::rooms;
+
+/// How the server accepts client links.
+#[derive(Clone, Copy, Debug)]
+pub enum ServerTransport {
+    /// Listen for netcode clients over UDP.
+    Udp { port: u16 },
+    /// No IO on the server entity; in-process links (e.g. crossbeam) are
+    /// attached to it as `LinkOf` by the caller.
+    Local,
+}
 
 pub struct ServerGamePlugin {
-    pub port: u16,
+    pub transport: ServerTransport,
+    /// Run the wave manager and difficulty director. Tests that place
+    /// enemies by hand turn this off.
+    pub spawn_waves: bool,
+    /// Match seed for wave generation; random when `None`.
+    pub seed: Option<u64>,
+    /// File the leaderboard is kept in; in memory only when `None`.
+    pub leaderboard: Option<PathBuf>,
+    pub rules: MatchRules,
 }
 
 impl Plugin for ServerGamePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(ServerConfig { port: self.port });
-
+        app.insert_resource(ServerConfig {
+            transport: self.transport,
+            seed: self.seed,
+            rules: self.rules,
+        });
+
+        app.insert_resource(match &self.leaderboard {
+            Some(path) => Leaderboard::load(path),
+            None => Leaderboard::in_memory(),
+        });
+
+        app.add_plugins(RoomPlugin);
         app.add_systems(Startup, setup_server);
         app.add_observer(handle_new_client);
         app.add_observer(handle_connected);
+        app.add_observer(replicate_enemies);
+        app.add_observer(replicate_powerups);
+        app.add_observer(replicate_projectiles);
+        app.add_observer(send_leaderboard);
+        app.add_observer(rooms::join_room);
+        app.add_observer(rooms::rejoin_room);
+        app.add_observer(rooms::handle_disconnected);
+        app.add_observer(rooms::show_to_room);
+        app.add_observer(rooms::hide_from_room);
+        app.add_observer(lifecycle::spend_life);
+        app.add_observer(lifecycle::record_result);
+        app.add_observer(lifecycle::reset_match);
+        app.add_plugins(SimulationPlugin);
+        app.add_plugins(AuthorityPlugin);
         app.add_systems(
             FixedUpdate,
             (
-                schizoid_shared::systems::ship_movement,
-                schizoid_shared::systems::enemy_movement,
-                schizoid_shared::systems::chaser_ai,
-                schizoid_shared::systems::collision_system,
-                schizoid_shared::systems::respawn_system,
-                wave_manager,
+                rooms::receive_join_requests,
+                rooms::handle_color_requests,
+                rooms::handle_ready,
+                lifecycle::handle_rematch,
+                lifecycle::advance_match,
+                rooms::close_empty_rooms,
             )
-                .chain(),
+                .chain()
+                .before(SimulationSystems),
         );
+        if self.spawn_waves {
+            app.add_plugins(WavesPlugin {
+                director: self.rules.director,
+            });
+        }
     }
 }
 
 #[derive(Resource)]
-struct ServerConfig {
-    port: u16,
-}
-
-#[derive(Resource, Default)]
-struct ConnectedClients {
-    count: usize,
+pub(crate) struct ServerConfig {
+    transport: ServerTransport,
+    pub(crate) seed: Option<u64>,
+    pub(crate) rules: MatchRules,
 }
 
 fn setup_server(mut commands: Commands, config: Res<ServerConfig>) {
-    commands.init_resource::<ConnectedClients>();
-
-    let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.port);
+    let mut server = commands.spawn((
+        Server::default(),
+        NetcodeServer::new(NetcodeConfig::default()),
+    ));
 
-    let server = commands
-        .spawn((
-            Server::default(),
-            NetcodeServer::new(NetcodeConfig::default()),
-            LocalAddr(addr),
-            ServerUdpIo::default(),
-        ))
-        .id();
+    if let ServerTransport::Udp { port } = config.transport {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), port);
+        server.insert((LocalAddr(addr), ServerUdpIo::default()));
+        info!("Server listening on port {}", port);
+    }
 
+    let server = server.id();
     commands.trigger(Start { entity: server });
-
-    info!("Server listening on port {}", config.port);
 }
 
 fn handle_new_client(trigger: On<Add, LinkOf>, mut commands: Commands) {
@@ -71,71 +120,50 @@ fn handle_new_client(trigger: On<Add, LinkOf>, mut commands: Commands) {
         ));
 }
 
-fn handle_connected(
-    trigger: On<Add, Connected>,
-    query: Query<&RemoteId, With<ClientOf>>,
-    mut commands: Commands,
-    mut connected: ResMut<ConnectedClients>,
-) {
+/// New clients wait in the lobby until they send `JoinRoom`; their ship is
+/// spawned when they take a seat. A client returning to a match it dropped
+/// out of is seated again by `rooms::rejoin_room`.
+fn handle_connected(trigger: On<Add, Connected>, query: Query<&RemoteId, With<ClientOf>>) {
     let client_id = query.get(trigger.entity).unwrap().0;
     info!("Client connected: {}", client_id);
+}
 
-    connected.count += 1;
-
-    // Alternate colors for each new connection
-    let color = if connected.count.is_multiple_of(2) {
-        TeamColor::Red
-    } else {
-        TeamColor::Blue
-    };
-
-    // Spawn player ship
-    commands.spawn((
-        Ship,
-        color,
-        Position(Vec2::ZERO),
-        Velocity(Vec2::ZERO),
-        Radius(SHIP_RADIUS),
-        Health::default(),
-        OwnedBy(client_id.to_bits()),
+/// Enemies are spawned by shared code; the server replicates them to the
+/// clients in their room, which predict them alongside their own ship.
+fn replicate_enemies(trigger: On<Add, EnemyType>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert((
         Replicate::to_clients(NetworkTarget::All),
-        PredictionTarget::to_clients(NetworkTarget::Single(client_id)),
-        InterpolationTarget::to_clients(NetworkTarget::AllExceptSingle(client_id)),
-        ControlledBy {
-            owner: trigger.entity,
-            lifetime: Default::default(),
-        },
+        PredictionTarget::to_clients(NetworkTarget::All),
     ));
+}
 
-    info!(
-        "Spawned ship for client {} with color {:?}",
-        client_id, color
-    );
+/// Pickups and the active rope are server-spawned and predicted by the
+/// clients in their room, so they can run `rope_system` on them.
+fn replicate_powerups(trigger: On<Add, (PowerUp, ElectricRope)>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert((
+        Replicate::to_clients(NetworkTarget::All),
+        PredictionTarget::to_clients(NetworkTarget::All),
+    ));
 }
 
-fn wave_manager(
-    mut wave: ResMut<WaveState>,
-    enemies: Query<Entity, With<EnemyType>>,
-    bounds: Res<ArenaBounds>,
-    mut commands: Commands,
-) {
-    // Count remaining enemies
-    wave.enemies_remaining = enemies.iter().count() as u32;
-
-    if wave.active && wave.enemies_remaining == 0 {
-        // Wave cleared, start breather
-        info!("Wave {} cleared, starting breather", wave.current_wave);
-        wave.active = false;
-        wave.breather_timer = WAVE_BREATHER;
-    }
+/// Shots are fired on the server; every client in the room predicts them
+/// so they fly and hit in step with the predicted enemies.
+fn replicate_projectiles(trigger: On<Add, Projectile>, mut commands: Commands) {
+    commands.entity(trigger.entity).insert((
+        Replicate::to_clients(NetworkTarget::All),
+        PredictionTarget::to_clients(NetworkTarget::All),
+    ));
+}
 
-    if !wave.active {
-        wave.breather_timer -= 1.0 / 60.0;
-        if wave.breather_timer <= 0.0 {
-            wave.current_wave += 1;
-            info!("Starting wave {}", wave.current_wave);
-            spawn_wave(&mut commands, wave.current_wave, &bounds);
-            wave.active = true;
-        }
+/// Every client gets the current leaderboard as soon as it connects.
+fn send_leaderboard(
+    trigger: On<Add, Connected>,
+    mut senders: Query<&mut MessageSender<LeaderboardMessage>>,
+    leaderboard: Res<Leaderboard>,
+) {
+    if let Ok(mut sender) = senders.get_mut(trigger.entity) {
+        sender.send::<ReliableChannel>(LeaderboardMessage {
+            entries: leaderboard.entries().to_vec(),
+        });
     }
 }
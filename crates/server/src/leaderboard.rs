@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use schizoid_shared::protocol::MatchResult;
+use std::path::PathBuf;
+
+/// How many results the leaderboard keeps.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Best match results on this server, best first: furthest wave, then
+/// highest team score. Saved as JSON after every change when backed by a file.
+#[derive(Resource, Debug, Default)]
+pub struct Leaderboard {
+    path: Option<PathBuf>,
+    entries: Vec<MatchResult>,
+}
+
+impl Leaderboard {
+    /// Load the leaderboard from `path`. A missing file starts empty; an
+    /// unreadable one is logged and replaced on the next save.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str(&json).unwrap_or_else(|err| {
+                warn!(
+                    "Ignoring unreadable leaderboard {}: {}",
+                    path.display(),
+                    err
+                );
+                Vec::new()
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => {
+                warn!("Could not read leaderboard {}: {}", path.display(), err);
+                Vec::new()
+            }
+        };
+
+        Self {
+            path: Some(path),
+            entries,
+        }
+    }
+
+    /// A leaderboard that is never written to disk.
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    pub fn entries(&self) -> &[MatchResult] {
+        &self.entries
+    }
+
+    /// Insert a finished match, keeping the best `LEADERBOARD_SIZE`, and save.
+    pub fn record(&mut self, result: MatchResult) {
+        let rank = self
+            .entries
+            .iter()
+            .position(|entry| (result.wave, result.team_score) > (entry.wave, entry.team_score))
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, result);
+        self.entries.truncate(LEADERBOARD_SIZE);
+
+        if let Err(err) = self.save() {
+            error!("Failed to save leaderboard: {}", err);
+        }
+    }
+
+    fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let json = serde_json::to_string_pretty(&self.entries)?;
+        std::fs::write(path, json)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod game;
+pub mod leaderboard;
+pub mod lifecycle;
+pub mod rooms;
@@ -0,0 +1,286 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use schizoid_shared::components::*;
+use schizoid_shared::director::{Director, DirectorConfig};
+use schizoid_shared::protocol::{
+    LeaderboardMessage, MatchResult, PlayerKills, ReliableChannel, RequestRematch,
+};
+use schizoid_shared::systems::ShipKilled;
+use schizoid_shared::waves::PendingGroups;
+
+use crate::game::ServerConfig;
+use crate::leaderboard::Leaderboard;
+use crate::rooms::Seat;
+
+/// How a match starts, and how it is lost.
+#[derive(Clone, Copy, Debug)]
+pub struct MatchRules {
+    /// Ready players a room needs before its countdown starts. Two fills
+    /// both colors.
+    pub players: usize,
+    /// Seconds between everyone being ready and play starting.
+    pub countdown: f32,
+    /// Lives the team shares for the whole match.
+    pub lives: u32,
+    /// How ships handle in each new room's matches.
+    pub handling: ShipHandling,
+    /// How the difficulty director adapts waves to the team.
+    pub director: DirectorConfig,
+    /// Seconds a room everyone dropped out of mid-match stays open for
+    /// them to come back to their parked ships.
+    pub reconnect_grace: f32,
+}
+
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            players: 2,
+            countdown: 3.0,
+            lives: 3,
+            handling: ShipHandling::Direct,
+            director: DirectorConfig::default(),
+            reconnect_grace: 60.0,
+        }
+    }
+}
+
+/// Server-side bookkeeping for a room's match in progress, kept on the
+/// match entity.
+#[derive(Component, Default)]
+pub(crate) struct MatchProgress {
+    /// Seconds spent `Playing`.
+    pub(crate) elapsed: f32,
+}
+
+/// The match in `room` ran out of lives. Triggered once per match by
+/// `spend_life`.
+#[derive(Event, Clone, Debug)]
+pub struct MatchEnded {
+    pub room: Entity,
+}
+
+/// A client in `room` asked for a rematch after a game over.
+#[derive(Event, Clone, Debug)]
+pub struct Rematch {
+    pub room: Entity,
+}
+
+/// Move each room's match through Lobby → Countdown → Playing. The
+/// countdown starts once enough players are seated and all of them are
+/// ready, and backs off to the lobby if that stops being true. `spend_life`
+/// ends the match and `reset_match` starts the next one.
+pub(crate) fn advance_match(
+    mut matches: Query<(&RoomName, &RoomPlayers, &mut MatchState, &mut MatchProgress)>,
+    config: Res<ServerConfig>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (name, players, mut state, mut progress) in matches.iter_mut() {
+        let ready = players.0.len() >= config.rules.players && players.all_ready();
+
+        match *state {
+            MatchState::Lobby => {
+                if ready {
+                    info!("Room {}: everyone is ready, starting countdown", name.0);
+                    *state = MatchState::Countdown {
+                        remaining: config.rules.countdown,
+                    };
+                }
+            }
+            MatchState::Countdown { .. } if !ready => {
+                info!("Room {}: countdown cancelled", name.0);
+                *state = MatchState::Lobby;
+            }
+            MatchState::Countdown { remaining } => {
+                let remaining = remaining - dt;
+                *state = if remaining <= 0.0 {
+                    info!("Room {}: match started", name.0);
+                    MatchState::Playing
+                } else {
+                    MatchState::Countdown { remaining }
+                };
+            }
+            MatchState::Playing => progress.elapsed += dt,
+            MatchState::GameOver => {}
+        }
+    }
+}
+
+/// Every ship death costs its team a life; the last one ends the match.
+pub(crate) fn spend_life(
+    trigger: On<ShipKilled>,
+    ships: Query<&InRoom>,
+    mut matches: Query<(&mut MatchState, &mut TeamLives)>,
+    mut commands: Commands,
+) {
+    let Ok(room) = ships.get(trigger.event().ship) else {
+        return;
+    };
+    let Ok((mut state, mut lives)) = matches.get_mut(room.0) else {
+        return;
+    };
+    if *state != MatchState::Playing {
+        return;
+    }
+
+    lives.0 = lives.0.saturating_sub(1);
+    if lives.0 == 0 {
+        *state = MatchState::GameOver;
+        commands.trigger(MatchEnded { room: room.0 });
+    }
+}
+
+/// Put the finished match on the leaderboard and push it to every client.
+/// The leaderboard is the whole server's, not the room's, so clients in
+/// other rooms and those still choosing one get the new entry too, as they
+/// got the board when they connected.
+pub(crate) fn record_result(
+    trigger: On<MatchEnded>,
+    ships: Query<(&TeamColor, &Score, &InRoom), With<Ship>>,
+    matches: Query<(&RoomName, &WaveState, &GameRng, &MatchProgress)>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut senders: Query<&mut MessageSender<LeaderboardMessage>, With<Connected>>,
+) {
+    let room = trigger.event().room;
+    let Ok((name, wave, rng, progress)) = matches.get(room) else {
+        return;
+    };
+    let ships: Vec<_> = ships
+        .iter()
+        .filter(|(.., in_room)| in_room.0 == room)
+        .map(|(color, score, _)| (*color, score))
+        .collect();
+
+    let mut kills: Vec<_> = ships
+        .iter()
+        .map(|(color, score)| PlayerKills {
+            color: *color,
+            kills: score.kills,
+        })
+        .collect();
+    kills.sort_by_key(|player| player.color != TeamColor::Red);
+
+    let result = MatchResult {
+        wave: wave.current_wave,
+        team_score: ships.iter().map(|(_, score)| score.points).sum(),
+        kills,
+        duration_secs: progress.elapsed,
+        seed: rng.seed(),
+    };
+    info!(
+        "Room {}: game over on wave {} with {} points after {:.0}s",
+        name.0, result.wave, result.team_score, result.duration_secs
+    );
+    leaderboard.record(result);
+
+    let message = LeaderboardMessage {
+        entries: leaderboard.entries().to_vec(),
+    };
+    for mut sender in senders.iter_mut() {
+        sender.send::<ReliableChannel>(message.clone());
+    }
+}
+
+/// Anyone seated in a room may call the rematch once its match is over.
+pub(crate) fn handle_rematch(
+    mut receivers: Query<(&mut MessageReceiver<RequestRematch>, Option<&Seat>)>,
+    matches: Query<&MatchState>,
+    mut commands: Commands,
+) {
+    let mut rooms = Vec::new();
+    for (mut receiver, seat) in receivers.iter_mut() {
+        if receiver.receive().count() == 0 {
+            continue;
+        }
+        if let Some(seat) = seat {
+            if !rooms.contains(&seat.room) {
+                rooms.push(seat.room);
+            }
+        }
+    }
+
+    for room in rooms {
+        if matches
+            .get(room)
+            .is_ok_and(|state| *state == MatchState::GameOver)
+        {
+            commands.trigger(Rematch { room });
+        }
+    }
+}
+
+/// Clear the room's arena and restore its ships, scores, lives and waves,
+/// then count down into a fresh match with a new seed (or the same fixed
+/// seed). Players who dropped out don't carry over: their parked ships go
+/// and their seats open up.
+#[allow(clippy::type_complexity)]
+pub(crate) fn reset_match(
+    trigger: On<Rematch>,
+    mut matches: Query<(&RoomName, &mut MatchState, &mut TeamLives, &mut RoomPlayers)>,
+    leftovers: Query<
+        (Entity, &InRoom),
+        Or<(
+            With<EnemyType>,
+            With<PowerUp>,
+            With<ElectricRope>,
+            With<Projectile>,
+        )>,
+    >,
+    mut ships: Query<
+        (
+            Entity,
+            &mut Position,
+            &mut Velocity,
+            &mut Health,
+            &mut Score,
+            &InRoom,
+            Has<Parked>,
+        ),
+        With<Ship>,
+    >,
+    config: Res<ServerConfig>,
+    director: Option<ResMut<Director>>,
+    mut commands: Commands,
+) {
+    let room = trigger.event().room;
+    let Ok((name, mut state, mut lives, mut players)) = matches.get_mut(room) else {
+        return;
+    };
+
+    for (entity, in_room) in leftovers.iter() {
+        if in_room.0 == room {
+            commands.entity(entity).despawn();
+        }
+    }
+    for (ship, mut pos, mut vel, mut health, mut score, in_room, parked) in ships.iter_mut() {
+        if in_room.0 != room {
+            continue;
+        }
+        if parked {
+            commands.entity(ship).despawn();
+        } else {
+            pos.0 = Vec2::ZERO;
+            vel.0 = Vec2::ZERO;
+            *health = Health::default();
+            *score = Score::default();
+        }
+    }
+    players.0.retain(|player| player.connected);
+
+    let seed = config.seed.unwrap_or_else(rand::random);
+    commands.entity(room).insert((
+        MatchSeed(seed),
+        WaveState::default(),
+        MatchProgress::default(),
+    ));
+    commands.entity(room).remove::<PendingGroups>();
+    if let Some(mut director) = director {
+        director.reset(room);
+    }
+    lives.0 = config.rules.lives;
+    *state = MatchState::Countdown {
+        remaining: config.rules.countdown,
+    };
+    info!("Room {}: rematch with seed {}", name.0, seed);
+}
@@ -1,35 +1,102 @@
 use bevy::app::ScheduleRunnerPlugin;
 use bevy::prelude::*;
 use clap::Parser;
-use schizoid_shared::{SharedPlugin, SERVER_PORT, TICK_DURATION};
-
-mod game;
+use schizoid_server::game::{ServerGamePlugin, ServerTransport};
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::archetypes::EnemyRegistry;
+use schizoid_shared::components::ShipHandling;
+use schizoid_shared::director::DirectorConfig;
+use schizoid_shared::waves::WaveTable;
+use schizoid_shared::{parse_tick_rate, SharedPlugin, SERVER_PORT, TICK_DURATION};
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(name = "schizoid-server")]
 struct Args {
     #[arg(short, long, default_value_t = SERVER_PORT)]
     port: u16,
+
+    /// Match seed for wave generation (random if omitted)
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Simulation tick rate in Hz (clients must use the same rate)
+    #[arg(long, value_parser = parse_tick_rate)]
+    tick_rate: Option<f64>,
+
+    /// Ready players a room needs before its match starts
+    #[arg(long, default_value_t = 2)]
+    players: usize,
+
+    /// Give ships momentum: they accelerate, coast and slide along walls
+    #[arg(long)]
+    momentum: bool,
+
+    /// Enemy archetypes to play with (the server and every client need the
+    /// same file); the built-in enemies if missing
+    #[arg(long, default_value = "assets/enemies.ron")]
+    enemies: PathBuf,
+
+    /// Keep waves at their base difficulty instead of adapting them to how
+    /// the team is doing
+    #[arg(long)]
+    no_director: bool,
+
+    /// Authored waves to play before the procedural ones take over; all
+    /// procedural if missing
+    #[arg(long, default_value = "assets/waves.ron")]
+    waves: PathBuf,
+
+    /// File to keep the leaderboard in
+    #[arg(long, default_value = "leaderboard.json")]
+    leaderboard: PathBuf,
 }
 
 fn main() {
     let args = Args::parse();
+    let tick_duration = args
+        .tick_rate
+        .map(|hz| Duration::from_secs_f64(1.0 / hz))
+        .unwrap_or(TICK_DURATION);
 
     let mut app = App::new();
 
     // Headless: no rendering, no window
-    app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(TICK_DURATION)));
+    app.add_plugins(MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(tick_duration)));
     app.add_plugins(bevy::log::LogPlugin::default());
 
     // Lightyear server
-    app.add_plugins(lightyear::prelude::server::ServerPlugins {
-        tick_duration: TICK_DURATION,
-    });
+    app.add_plugins(lightyear::prelude::server::ServerPlugins { tick_duration });
 
     // Game
     app.add_plugins(SharedPlugin);
-    app.add_plugins(game::ServerGamePlugin { port: args.port });
+    let registry = EnemyRegistry::load(&args.enemies);
+    app.insert_resource(WaveTable::load(&args.waves, &registry));
+    app.insert_resource(registry);
+    app.add_plugins(ServerGamePlugin {
+        transport: ServerTransport::Udp { port: args.port },
+        spawn_waves: true,
+        seed: args.seed,
+        leaderboard: Some(args.leaderboard),
+        rules: MatchRules {
+            players: args.players,
+            handling: match args.momentum {
+                true => ShipHandling::Momentum(default()),
+                false => ShipHandling::Direct,
+            },
+            director: DirectorConfig {
+                enabled: !args.no_director,
+                ..default()
+            },
+            ..default()
+        },
+    });
 
-    info!("Starting server on port {}", args.port);
+    info!(
+        "Starting server on port {} at {:.1} Hz",
+        args.port,
+        1.0 / tick_duration.as_secs_f64()
+    );
     app.run();
 }
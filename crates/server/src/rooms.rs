@@ -0,0 +1,498 @@
+use bevy::prelude::*;
+use lightyear::prelude::server::ClientOf;
+use lightyear::prelude::*;
+use schizoid_shared::components::*;
+use schizoid_shared::director::Director;
+use schizoid_shared::protocol::{ChooseColor, JoinRoom, Rejected, ReliableChannel, SetReady};
+
+use crate::game::ServerConfig;
+use crate::lifecycle::MatchProgress;
+
+/// Where a client sits: its room and the ships it flies there, one for a
+/// normal player and both for one flying both ships. Kept on the client's
+/// `ClientOf` entity.
+#[derive(Component, Clone, Debug)]
+pub struct Seat {
+    pub room: Entity,
+    pub ships: Vec<Entity>,
+}
+
+/// A client asked for a seat in the room called `room`, optionally in a
+/// given color, or for both seats with `both_ships`. Triggered by
+/// `receive_join_requests`, one request at a time, so a room created for
+/// one request is there for the next.
+#[derive(Event, Clone, Debug)]
+pub struct JoinRequested {
+    pub client: Entity,
+    pub room: String,
+    pub color: Option<TeamColor>,
+    pub both_ships: bool,
+}
+
+pub(crate) fn receive_join_requests(
+    mut receivers: Query<(Entity, &mut MessageReceiver<JoinRoom>)>,
+    mut commands: Commands,
+) {
+    for (client, mut receiver) in receivers.iter_mut() {
+        for request in receiver.receive() {
+            commands.trigger(JoinRequested {
+                client,
+                room: request.room,
+                color: request.color,
+                both_ships: request.both_ships,
+            });
+        }
+    }
+}
+
+/// The colors a join request would take in a room with these players: both
+/// for a client flying both ships, otherwise the one asked for or the first
+/// free one.
+fn pick_colors(players: &RoomPlayers, request: &JoinRequested) -> Result<Vec<TeamColor>, Rejected> {
+    if request.both_ships {
+        return match [TeamColor::Red, TeamColor::Blue]
+            .into_iter()
+            .find(|color| players.has_color(*color))
+        {
+            Some(color) => Err(Rejected::ColorTaken(color)),
+            None => Ok(vec![TeamColor::Red, TeamColor::Blue]),
+        };
+    }
+    match request.color {
+        Some(color) if players.has_color(color) => Err(Rejected::ColorTaken(color)),
+        Some(color) => Ok(vec![color]),
+        None => players
+            .free_color()
+            .map(|color| vec![color])
+            .ok_or_else(|| Rejected::RoomFull(request.room.clone())),
+    }
+}
+
+/// Seat the client in the named room with the color it asked for, or the
+/// first free one (both, for a client flying both ships), creating the room
+/// if needed. A full room, or a taken color, turns the client away with a
+/// `Rejected`, leaving it where it was. Taking the color of a player who
+/// dropped out mid-match takes over their parked ship.
+pub(crate) fn join_room(
+    trigger: On<JoinRequested>,
+    mut clients: Query<(&RemoteId, Option<&Seat>, &mut MessageSender<Rejected>)>,
+    mut rooms: Query<(Entity, &RoomName, &mut RoomPlayers)>,
+    parked: Query<(Entity, &TeamColor, &InRoom), With<Parked>>,
+    config: Res<ServerConfig>,
+    mut commands: Commands,
+) {
+    let event = trigger.event();
+    let Ok((remote_id, seat, mut rejections)) = clients.get_mut(event.client) else {
+        return;
+    };
+    let client_id = remote_id.0.to_bits();
+
+    let target = rooms
+        .iter()
+        .find(|(_, name, _)| name.0 == event.room)
+        .map(|(room, _, players)| (room, pick_colors(players, event)));
+    let colors = match (target.clone(), seat) {
+        (Some((room, _)), Some(seat)) if seat.room == room => return,
+        (Some((_, Err(rejection))), _) => {
+            warn!(
+                "Client {} cannot join room {}: {:?}",
+                remote_id.0, event.room, rejection
+            );
+            rejections.send::<ReliableChannel>(rejection);
+            return;
+        }
+        (Some((_, Ok(colors))), _) => colors,
+        (None, _) => {
+            pick_colors(&RoomPlayers::default(), event).expect("an empty room has every color free")
+        }
+    };
+
+    if let Some(seat) = seat {
+        if let Ok((_, _, mut players)) = rooms.get_mut(seat.room) {
+            leave_room(&mut commands, event.client, client_id, seat, &mut players);
+        }
+    }
+
+    let joining: Vec<_> = colors
+        .iter()
+        .map(|color| RoomPlayer {
+            client: client_id,
+            color: *color,
+            ready: false,
+            connected: true,
+        })
+        .collect();
+    let mut abandoned = Vec::new();
+    let room = match target.and_then(|(room, _)| rooms.get_mut(room).ok()) {
+        Some((room, _, mut players)) => {
+            players.0.retain(|player| {
+                let taken_over = !player.connected && colors.contains(&player.color);
+                if taken_over {
+                    abandoned.push(player.color);
+                }
+                !taken_over
+            });
+            players.0.extend(joining);
+            room
+        }
+        None => spawn_room(&mut commands, &event.room, joining, &config),
+    };
+
+    let ships = colors
+        .iter()
+        .map(|color| {
+            parked
+                .iter()
+                .find(|(_, ship_color, in_room)| {
+                    abandoned.contains(color) && *ship_color == color && in_room.0 == room
+                })
+                .map(|(ship, ..)| ship)
+                .unwrap_or_else(|| {
+                    commands
+                        .spawn((
+                            Ship,
+                            *color,
+                            Position(Vec2::ZERO),
+                            Velocity(Vec2::ZERO),
+                            Radius(SHIP_RADIUS),
+                            Health::default(),
+                            Score::default(),
+                            Weapon::default(),
+                            InRoom(room),
+                            Replicate::to_clients(NetworkTarget::All),
+                        ))
+                        .id()
+                })
+        })
+        .collect();
+    take_seat(
+        &mut commands,
+        event.client,
+        remote_id.0,
+        Seat { room, ships },
+    );
+
+    info!(
+        "Client {} joined room {} as {:?}",
+        remote_id.0, event.room, colors
+    );
+}
+
+/// Put the client on `link` in the pilot's seat of each of `seat.ships`:
+/// they take that client's input, and the room replicates to the client.
+/// The pilot predicts its ships; everyone else interpolates them, having no
+/// input to predict them with. Ships outlive the connection so a client who
+/// drops out can come back.
+fn take_seat(commands: &mut Commands, link: Entity, peer: PeerId, seat: Seat) {
+    for ship in &seat.ships {
+        commands.entity(*ship).remove::<Parked>().insert((
+            OwnedBy(peer.to_bits()),
+            ControlledBy {
+                owner: link,
+                lifetime: Lifetime::Persistent,
+            },
+            PredictionTarget::to_clients(NetworkTarget::Single(peer)),
+            InterpolationTarget::to_clients(NetworkTarget::AllExceptSingle(peer)),
+        ));
+    }
+    commands.trigger(RoomEvent {
+        room: seat.room,
+        target: RoomTarget::AddSender(link),
+    });
+    commands.entity(link).insert(seat);
+}
+
+/// A client coming back to a match it dropped out of gets its parked ships,
+/// and their scores, back.
+pub(crate) fn rejoin_room(
+    trigger: On<Add, Connected>,
+    clients: Query<&RemoteId, With<ClientOf>>,
+    mut rooms: Query<(Entity, &RoomName, &mut RoomPlayers)>,
+    parked: Query<(Entity, &OwnedBy, &InRoom), With<Parked>>,
+    mut commands: Commands,
+) {
+    let Ok(remote_id) = clients.get(trigger.entity) else {
+        return;
+    };
+    let client_id = remote_id.0.to_bits();
+
+    for (room, name, mut players) in rooms.iter_mut() {
+        let ships: Vec<_> = parked
+            .iter()
+            .filter(|(_, owned_by, in_room)| owned_by.0 == client_id && in_room.0 == room)
+            .map(|(ship, ..)| ship)
+            .collect();
+        if ships.is_empty() {
+            continue;
+        }
+
+        for player in players.of_client_mut(client_id) {
+            player.connected = true;
+        }
+        take_seat(
+            &mut commands,
+            trigger.entity,
+            remote_id.0,
+            Seat { room, ships },
+        );
+        info!("Client {} is back in room {}", remote_id.0, name.0);
+        return;
+    }
+}
+
+/// A seated client dropped out. Before its room's match starts the seat is
+/// simply given up; once it's underway its ships are parked, frozen and out
+/// of harm's way, and their colors are free for someone else to take over.
+pub(crate) fn handle_disconnected(
+    trigger: On<Add, Disconnected>,
+    clients: Query<(&RemoteId, &Seat)>,
+    mut rooms: Query<(&RoomName, &MatchState, &mut RoomPlayers)>,
+    mut commands: Commands,
+) {
+    let Ok((remote_id, seat)) = clients.get(trigger.entity) else {
+        return;
+    };
+    let Ok((name, state, mut players)) = rooms.get_mut(seat.room) else {
+        return;
+    };
+    let client_id = remote_id.0.to_bits();
+
+    if matches!(state, MatchState::Lobby | MatchState::Countdown { .. }) {
+        leave_room(&mut commands, trigger.entity, client_id, seat, &mut players);
+        info!("Client {} left room {}", remote_id.0, name.0);
+        return;
+    }
+
+    for player in players.of_client_mut(client_id) {
+        player.connected = false;
+    }
+    for ship in &seat.ships {
+        commands
+            .entity(*ship)
+            .remove::<ControlledBy>()
+            .insert(Parked);
+    }
+    commands.trigger(RoomEvent {
+        room: seat.room,
+        target: RoomTarget::RemoveSender(trigger.entity),
+    });
+    info!(
+        "Client {} dropped out of room {}; parking its ships",
+        remote_id.0, name.0
+    );
+}
+
+/// Open a room for its first player (or both seats of a client flying both
+/// ships): the match entity everyone seated in it shares. Clients seed their
+/// GameRng from its MatchSeed and follow its MatchState.
+fn spawn_room(
+    commands: &mut Commands,
+    name: &str,
+    players: Vec<RoomPlayer>,
+    config: &ServerConfig,
+) -> Entity {
+    let seed = config.seed.unwrap_or_else(rand::random);
+    let room = commands
+        .spawn((
+            Room::default(),
+            RoomName(name.to_string()),
+            RoomPlayers(players),
+            MatchSeed(seed),
+            MatchState::Lobby,
+            TeamLives(config.rules.lives),
+            config.rules.handling,
+            WaveState::default(),
+            MatchProgress::default(),
+            Replicate::to_clients(NetworkTarget::All),
+            NetworkVisibility,
+        ))
+        .id();
+    commands.trigger(RoomEvent {
+        room,
+        target: RoomTarget::AddEntity(room),
+    });
+    info!("Opened room {} with seed {}", name, seed);
+    room
+}
+
+/// Give up a client's seat: its ships go and the room stops replicating
+/// to it.
+fn leave_room(
+    commands: &mut Commands,
+    client: Entity,
+    client_id: u64,
+    seat: &Seat,
+    players: &mut RoomPlayers,
+) {
+    players.0.retain(|player| player.client != client_id);
+    for ship in &seat.ships {
+        commands.entity(*ship).despawn();
+    }
+    commands.entity(client).remove::<Seat>();
+    commands.trigger(RoomEvent {
+        room: seat.room,
+        target: RoomTarget::RemoveSender(client),
+    });
+}
+
+/// Counts down the time left for someone to come back to a room everyone
+/// dropped out of mid-match.
+#[derive(Component, Debug)]
+pub(crate) struct Deserted {
+    remaining: f32,
+}
+
+/// Close rooms nobody is connected to any more. A room whose last seat was
+/// given up closes straight away; one everyone dropped out of mid-match
+/// waits `MatchRules::reconnect_grace` for them to come back to their parked
+/// ships first. Everything in the room goes with it.
+#[allow(clippy::type_complexity)]
+pub(crate) fn close_empty_rooms(
+    mut rooms: Query<(Entity, &RoomName, &RoomPlayers, Option<&mut Deserted>)>,
+    members: Query<(Entity, &InRoom)>,
+    parked: Query<&InRoom, With<Parked>>,
+    config: Res<ServerConfig>,
+    time: Res<Time<Fixed>>,
+    mut director: Option<ResMut<Director>>,
+    mut commands: Commands,
+) {
+    for (room, name, players, deserted) in rooms.iter_mut() {
+        if players.0.iter().any(|player| player.connected) {
+            if deserted.is_some() {
+                commands.entity(room).remove::<Deserted>();
+            }
+            continue;
+        }
+        if parked.iter().any(|in_room| in_room.0 == room) {
+            match deserted {
+                None => {
+                    commands.entity(room).insert(Deserted {
+                        remaining: config.rules.reconnect_grace,
+                    });
+                    info!("Room {}: everyone dropped out, holding it open", name.0);
+                    continue;
+                }
+                Some(mut deserted) => {
+                    deserted.remaining -= time.delta_secs();
+                    if deserted.remaining > 0.0 {
+                        continue;
+                    }
+                }
+            }
+        }
+        // Members first, while the room is still there to drop them from
+        for (member, _) in members.iter().filter(|(_, in_room)| in_room.0 == room) {
+            commands.entity(member).despawn();
+        }
+        commands.entity(room).despawn();
+        if let Some(director) = director.as_mut() {
+            director.reset(room);
+        }
+        info!("Closed room {}: nobody is left in it", name.0);
+    }
+}
+
+/// Everything placed in a room is replicated only to the clients seated in it.
+pub(crate) fn show_to_room(
+    trigger: On<Add, InRoom>,
+    members: Query<&InRoom>,
+    mut commands: Commands,
+) {
+    let Ok(in_room) = members.get(trigger.entity) else {
+        return;
+    };
+    commands.entity(trigger.entity).insert(NetworkVisibility);
+    commands.trigger(RoomEvent {
+        room: in_room.0,
+        target: RoomTarget::AddEntity(trigger.entity),
+    });
+}
+
+/// Drop despawned entities from their room's bookkeeping.
+pub(crate) fn hide_from_room(
+    trigger: On<Remove, InRoom>,
+    members: Query<&InRoom>,
+    rooms: Query<(), With<Room>>,
+    mut commands: Commands,
+) {
+    let Ok(in_room) = members.get(trigger.entity) else {
+        return;
+    };
+    if rooms.contains(in_room.0) {
+        commands.trigger(RoomEvent {
+            room: in_room.0,
+            target: RoomTarget::RemoveEntity(trigger.entity),
+        });
+    }
+}
+
+/// Switch a seated client's color while its room is still in the lobby, as
+/// long as nobody else has it. Otherwise the client gets a `Rejected`; a
+/// client flying both ships already has both.
+#[allow(clippy::type_complexity)]
+pub(crate) fn handle_color_requests(
+    mut clients: Query<(
+        &RemoteId,
+        Option<&Seat>,
+        &mut MessageReceiver<ChooseColor>,
+        &mut MessageSender<Rejected>,
+    )>,
+    mut rooms: Query<(&MatchState, &mut RoomPlayers)>,
+    mut ships: Query<&mut TeamColor, With<Ship>>,
+) {
+    for (remote_id, seat, mut receiver, mut rejections) in clients.iter_mut() {
+        for ChooseColor(color) in receiver.receive() {
+            let Some(seat) = seat else {
+                continue;
+            };
+            let Ok((state, mut players)) = rooms.get_mut(seat.room) else {
+                continue;
+            };
+            let client_id = remote_id.0.to_bits();
+            let taken = seat.ships.len() > 1
+                || players
+                    .0
+                    .iter()
+                    .any(|player| player.client != client_id && player.color == color);
+            let rejection = match (*state, taken) {
+                (MatchState::Lobby, false) => None,
+                (MatchState::Lobby, true) => Some(Rejected::ColorTaken(color)),
+                _ => Some(Rejected::MatchUnderway),
+            };
+            if let Some(rejection) = rejection {
+                rejections.send::<ReliableChannel>(rejection);
+                continue;
+            }
+
+            for player in players.of_client_mut(client_id) {
+                player.color = color;
+            }
+            for ship in &seat.ships {
+                if let Ok(mut ship_color) = ships.get_mut(*ship) {
+                    *ship_color = color;
+                }
+            }
+        }
+    }
+}
+
+/// Record each seated client's ready flag on its room's roster (on both
+/// seats, for a client flying both ships).
+pub(crate) fn handle_ready(
+    mut clients: Query<(&RemoteId, Option<&Seat>, &mut MessageReceiver<SetReady>)>,
+    mut rooms: Query<&mut RoomPlayers>,
+) {
+    for (remote_id, seat, mut receiver) in clients.iter_mut() {
+        for SetReady(ready) in receiver.receive() {
+            let Some(seat) = seat else {
+                continue;
+            };
+            let Ok(mut players) = rooms.get_mut(seat.room) else {
+                continue;
+            };
+            for player in players.of_client_mut(remote_id.0.to_bits()) {
+                player.ready = ready;
+            }
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::components::*;
+
+/// One kind of enemy as designers declare it in the enemies file. Its
+/// `behaviour` picks the AI the game runs it with; everything else tunes it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnemyArchetype {
+    /// What enemies of this archetype are called, in `Archetype` and in
+    /// other archetypes (e.g. a splitter's `child`).
+    pub name: String,
+    pub behaviour: Behaviour,
+    /// Units per second; for orbiters, radians per second around the orbit.
+    pub speed: f32,
+    pub radius: f32,
+    /// Base points for destroying one, before the combo multiplier.
+    pub points: u32,
+    /// Same-color hits it takes to destroy; more than one gives it
+    /// `EnemyHealth`.
+    #[serde(default = "hit_points")]
+    pub hit_points: u32,
+    /// Relative odds of each enemy in a wave being this archetype. Zero
+    /// keeps it out of waves (e.g. splitter children).
+    pub spawn_weight: f32,
+    /// Added to `spawn_weight` for every wave after `first_wave`.
+    #[serde(default)]
+    pub weight_per_wave: f32,
+    /// Cap on the weight `weight_per_wave` can grow to.
+    #[serde(default)]
+    pub max_weight: Option<f32>,
+    /// Earliest wave this archetype shows up in.
+    #[serde(default = "first_wave")]
+    pub first_wave: u32,
+    /// How it's drawn; the behaviour's usual look when left out.
+    #[serde(default)]
+    pub shape: Option<Shape>,
+}
+
+fn first_wave() -> u32 {
+    1
+}
+
+fn hit_points() -> u32 {
+    1
+}
+
+impl EnemyArchetype {
+    /// This archetype's odds in `wave`, zero before its first wave.
+    pub fn weight(&self, wave: u32) -> f32 {
+        if wave < self.first_wave {
+            return 0.0;
+        }
+        let weight = self.spawn_weight + self.weight_per_wave * (wave - self.first_wave) as f32;
+        match self.max_weight {
+            Some(max) => weight.min(max),
+            None => weight,
+        }
+    }
+}
+
+/// The AI an archetype runs, with its parameters.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum Behaviour {
+    /// Flies straight, bouncing off the walls.
+    Drifter,
+    /// Heads for the nearest ship of the other color.
+    Chaser,
+    /// Circles a point somewhere in the inner arena.
+    Orbiter { orbit_radius: f32 },
+    /// Drifts; a same-color kill splits it into two of the `child` archetype,
+    /// which should be a drifter or chaser as children get no orbit or facing.
+    Splitter { child: String },
+    /// Turns toward the nearest ship at `turn_rate` radians per second
+    /// behind a shield that pushes ships `knockback` units clear.
+    Shielder { turn_rate: f32, knockback: f32 },
+    /// Stalks the nearest ship of the other color and charges at it, moving
+    /// through `phases` as its hit points run down.
+    Boss { phases: Vec<BossPhase> },
+}
+
+/// One stage of a boss fight.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BossPhase {
+    /// Entered once the boss is down to this fraction of its hit points;
+    /// the first phase should start at 1.0.
+    pub below: f32,
+    /// Swap to the other color on entering, handing the fight to the other
+    /// ship.
+    #[serde(default)]
+    pub flip_color: bool,
+    /// Called in around the boss on entering.
+    #[serde(default)]
+    pub minions: Option<Minions>,
+    /// Seconds between charges at the nearest ship.
+    pub charge_every: f32,
+}
+
+/// Enemies a boss phase calls in, of random colors.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Minions {
+    pub archetype: String,
+    pub count: u32,
+}
+
+impl Behaviour {
+    /// The replicated tag systems select this behaviour's enemies by.
+    pub fn kind(&self) -> EnemyType {
+        match self {
+            Behaviour::Drifter => EnemyType::Drifter,
+            Behaviour::Chaser => EnemyType::Chaser,
+            Behaviour::Orbiter { .. } => EnemyType::Orbiter,
+            Behaviour::Splitter { .. } => EnemyType::Splitter,
+            Behaviour::Shielder { .. } => EnemyType::Shielder,
+            Behaviour::Boss { .. } => EnemyType::Boss,
+        }
+    }
+}
+
+/// Mesh an archetype is drawn with, at its radius.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Shape {
+    Circle,
+    Ring,
+    /// At least three sides.
+    Polygon(u32),
+}
+
+/// Every enemy archetype in play. Loaded from the enemies file on the
+/// server and each client (they must agree, as clients predict enemies
+/// with it); the built-in archetypes when there is none.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EnemyRegistry {
+    pub archetypes: Vec<EnemyArchetype>,
+}
+
+impl Default for EnemyRegistry {
+    fn default() -> Self {
+        let archetype =
+            |name: &str, behaviour, speed, radius, points, spawn_weight| EnemyArchetype {
+                name: name.to_string(),
+                behaviour,
+                speed,
+                radius,
+                points,
+                hit_points: 1,
+                spawn_weight,
+                weight_per_wave: 0.0,
+                max_weight: None,
+                first_wave: 1,
+                shape: None,
+            };
+        // Listed in the order waves roll for them
+        Self {
+            archetypes: vec![
+                EnemyArchetype {
+                    weight_per_wave: 0.1,
+                    max_weight: Some(0.6),
+                    ..archetype(
+                        "chaser",
+                        Behaviour::Chaser,
+                        CHASER_SPEED,
+                        ENEMY_RADIUS,
+                        EnemyType::Chaser.points(),
+                        0.1,
+                    )
+                },
+                archetype(
+                    "orbiter",
+                    Behaviour::Orbiter {
+                        orbit_radius: ORBITER_RADIUS,
+                    },
+                    ORBITER_SPEED,
+                    ENEMY_RADIUS,
+                    EnemyType::Orbiter.points(),
+                    0.2,
+                ),
+                EnemyArchetype {
+                    first_wave: 2,
+                    ..archetype(
+                        "splitter",
+                        Behaviour::Splitter {
+                            child: "splitter_child".to_string(),
+                        },
+                        SPLITTER_SPEED,
+                        SPLITTER_RADIUS,
+                        EnemyType::Splitter.points(),
+                        0.15,
+                    )
+                },
+                EnemyArchetype {
+                    first_wave: 4,
+                    ..archetype(
+                        "shielder",
+                        Behaviour::Shielder {
+                            turn_rate: SHIELDER_TURN_RATE,
+                            knockback: SHIELDER_KNOCKBACK,
+                        },
+                        SHIELDER_SPEED,
+                        SHIELDER_RADIUS,
+                        EnemyType::Shielder.points(),
+                        0.05,
+                    )
+                },
+                archetype(
+                    "drifter",
+                    Behaviour::Drifter,
+                    DRIFTER_SPEED,
+                    ENEMY_RADIUS,
+                    EnemyType::Drifter.points(),
+                    0.7,
+                ),
+                archetype(
+                    "splitter_child",
+                    Behaviour::Drifter,
+                    SPLITTER_CHILD_SPEED,
+                    SPLITTER_CHILD_RADIUS,
+                    EnemyType::Drifter.points(),
+                    0.0,
+                ),
+                EnemyArchetype {
+                    hit_points: BOSS_HIT_POINTS,
+                    ..archetype(
+                        "boss",
+                        Behaviour::Boss {
+                            phases: vec![
+                                BossPhase {
+                                    below: 1.0,
+                                    flip_color: false,
+                                    minions: None,
+                                    charge_every: 4.0,
+                                },
+                                BossPhase {
+                                    below: 0.66,
+                                    flip_color: true,
+                                    minions: Some(Minions {
+                                        archetype: "drifter".to_string(),
+                                        count: 4,
+                                    }),
+                                    charge_every: 3.0,
+                                },
+                                BossPhase {
+                                    below: 0.33,
+                                    flip_color: true,
+                                    minions: Some(Minions {
+                                        archetype: "chaser".to_string(),
+                                        count: 3,
+                                    }),
+                                    charge_every: 2.0,
+                                },
+                            ],
+                        },
+                        BOSS_SPEED,
+                        BOSS_RADIUS,
+                        EnemyType::Boss.points(),
+                        0.0,
+                    )
+                },
+            ],
+        }
+    }
+}
+
+impl EnemyRegistry {
+    /// Read the archetypes from a RON file. A missing or unreadable file
+    /// leaves the built-in archetypes, which is logged.
+    pub fn load(path: &Path) -> Self {
+        let registry = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|ron| ron::from_str::<Self>(&ron).map_err(|err| err.to_string()));
+        match registry {
+            Ok(mut registry) => {
+                registry.check();
+                info!(
+                    "Loaded {} enemy archetypes from {}",
+                    registry.archetypes.len(),
+                    path.display()
+                );
+                registry
+            }
+            Err(err) => {
+                warn!(
+                    "Using the built-in enemies, could not load {}: {}",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Warn about splitters whose children, and bosses whose minions, can't
+    /// be spawned. Radii and shapes that can't be drawn are replaced, as
+    /// clients can't build meshes from them, and negative or non-finite
+    /// weights are zeroed, as they would throw off every `pick`.
+    fn check(&mut self) {
+        for archetype in &mut self.archetypes {
+            let name = &archetype.name;
+            zero_bad_weight(name, "spawn_weight", &mut archetype.spawn_weight);
+            zero_bad_weight(name, "weight_per_wave", &mut archetype.weight_per_wave);
+            if let Some(max) = &mut archetype.max_weight {
+                zero_bad_weight(name, "max_weight", max);
+            }
+            if !(archetype.radius > 0.0 && archetype.radius.is_finite()) {
+                warn!(
+                    "Enemy archetype {} has radius {}, using {}",
+                    archetype.name, archetype.radius, ENEMY_RADIUS
+                );
+                archetype.radius = ENEMY_RADIUS;
+            }
+            if let Some(Shape::Polygon(sides @ 0..3)) = archetype.shape {
+                warn!(
+                    "Enemy archetype {} is drawn as a polygon with {} sides, using its usual look",
+                    archetype.name, sides
+                );
+                archetype.shape = None;
+            }
+        }
+        for archetype in &self.archetypes {
+            match &archetype.behaviour {
+                Behaviour::Splitter { child } => {
+                    match self.get(child).map(|child| &child.behaviour) {
+                        Some(Behaviour::Drifter | Behaviour::Chaser) => {}
+                        Some(_) => warn!(
+                            "Enemy archetype {} splits into {}, which is neither a drifter nor a chaser",
+                            archetype.name, child
+                        ),
+                        None => warn!(
+                            "Enemy archetype {} splits into unknown archetype {}",
+                            archetype.name, child
+                        ),
+                    }
+                }
+                Behaviour::Boss { phases } => {
+                    for minions in phases.iter().filter_map(|phase| phase.minions.as_ref()) {
+                        if self.get(&minions.archetype).is_none() {
+                            warn!(
+                                "Boss {} calls in unknown archetype {}",
+                                archetype.name, minions.archetype
+                            );
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The archetype called `name`.
+    pub fn get(&self, name: &str) -> Option<&EnemyArchetype> {
+        self.archetypes
+            .iter()
+            .find(|archetype| archetype.name == name)
+    }
+
+    /// The first boss archetype, fought every `BOSS_WAVE_INTERVAL` waves.
+    pub fn boss(&self) -> Option<&EnemyArchetype> {
+        self.archetypes
+            .iter()
+            .find(|archetype| matches!(archetype.behaviour, Behaviour::Boss { .. }))
+    }
+
+    /// The archetype an enemy was spawned as.
+    pub fn of(&self, archetype: &Archetype) -> Option<&EnemyArchetype> {
+        self.get(&archetype.0)
+    }
+
+    /// The archetype a `roll` in `[0, 1)` lands on among those that can
+    /// show up in `wave`, by weight, with chasers' weight scaled by
+    /// `chaser_share`.
+    pub fn pick(&self, wave: u32, roll: f32, chaser_share: f32) -> Option<&EnemyArchetype> {
+        let weight = |archetype: &EnemyArchetype| match archetype.behaviour {
+            Behaviour::Chaser => archetype.weight(wave) * chaser_share,
+            _ => archetype.weight(wave),
+        };
+        let total: f32 = self
+            .archetypes
+            .iter()
+            .map(weight)
+            .filter(|weight| *weight > 0.0)
+            .sum();
+        let mut remaining = roll * total;
+        let mut candidates = self
+            .archetypes
+            .iter()
+            .filter(|archetype| weight(archetype) > 0.0);
+        let last = candidates.clone().next_back();
+        candidates
+            .find(|archetype| {
+                remaining -= weight(archetype);
+                remaining < 0.0
+            })
+            .or(last)
+    }
+}
+
+/// Zero an archetype's `field` weight if it's negative or not finite.
+fn zero_bad_weight(archetype: &str, field: &str, weight: &mut f32) {
+    if !(*weight >= 0.0 && weight.is_finite()) {
+        warn!(
+            "Enemy archetype {} has {} {}, using 0",
+            archetype, field, weight
+        );
+        *weight = 0.0;
+    }
+}
+
+/// Warn the first time an enemy turns up with an archetype missing from the
+/// registry, e.g. when the server and a client load different enemies
+/// files. Systems leave such enemies be.
+pub(crate) fn warn_unknown_archetype(
+    trigger: On<Insert, Archetype>,
+    archetypes: Query<&Archetype>,
+    registry: Res<EnemyRegistry>,
+    mut warned: Local<Vec<String>>,
+) {
+    let Ok(archetype) = archetypes.get(trigger.entity) else {
+        return;
+    };
+    if registry.of(archetype).is_none() && !warned.contains(&archetype.0) {
+        warn!(
+            "Enemy has unknown archetype {}, it won't move or collide",
+            archetype.0
+        );
+        warned.push(archetype.0.clone());
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+
+use crate::archetypes::{Behaviour, BossPhase, EnemyRegistry};
+use crate::components::*;
+use crate::enemies::formations::Formation;
+use crate::waves::{spawn_group, GroupColor, SpawnGroup};
+use crate::Rooms;
+
+/// The phases of the boss archetype an enemy was spawned as.
+fn phases<'a>(registry: &'a EnemyRegistry, archetype: &Archetype) -> Option<&'a [BossPhase]> {
+    match &registry.of(archetype)?.behaviour {
+        Behaviour::Boss { phases } => Some(phases),
+        _ => None,
+    }
+}
+
+/// Bosses stalk the nearest ship of the other color and, every
+/// `charge_every` seconds of their phase, charge at it for
+/// `BOSS_CHARGE_TIME`. A charge keeps its line, bouncing off walls.
+#[allow(clippy::type_complexity)]
+pub fn boss_ai(
+    mut bosses: Query<
+        (
+            &mut BossState,
+            &mut Velocity,
+            &Position,
+            &TeamColor,
+            &Archetype,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (With<EnemyType>, Without<Ship>, Without<SpawnWarning>),
+    >,
+    ships: Query<(&Position, &TeamColor, &Health, Option<&InRoom>), (With<Ship>, Without<Parked>)>,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut state, mut vel, boss_pos, boss_color, archetype, scale, boss_room) in bosses.iter_mut()
+    {
+        if !rooms.playing(boss_room) {
+            continue;
+        }
+        let (Some(stats), Some(phases)) = (registry.of(archetype), phases(&registry, archetype))
+        else {
+            continue;
+        };
+        let speed = stats.speed * scale.map_or(1.0, |scale| scale.0);
+
+        if state.charging > 0.0 {
+            state.charging = (state.charging - dt).max(0.0);
+            continue;
+        }
+
+        let target = ships
+            .iter()
+            .filter(|(_, color, health, room)| {
+                **color != *boss_color && health.alive && *room == boss_room
+            })
+            .map(|(pos, ..)| pos.0)
+            .min_by(|a, b| {
+                a.distance_squared(boss_pos.0)
+                    .total_cmp(&b.distance_squared(boss_pos.0))
+            });
+        let Some(target) = target else {
+            vel.0 = Vec2::ZERO;
+            continue;
+        };
+        let dir = (target - boss_pos.0).normalize_or_zero();
+
+        state.charge_timer -= dt;
+        if state.charge_timer <= 0.0 {
+            state.charge_timer = phases
+                .get(state.phase)
+                .map_or(f32::INFINITY, |phase| phase.charge_every);
+            state.charging = BOSS_CHARGE_TIME;
+            vel.0 = dir * speed * BOSS_CHARGE_SPEEDUP;
+        } else {
+            vel.0 = dir * speed;
+        }
+    }
+}
+
+/// Move bosses into the phases their hit points have fallen to: each phase
+/// entered may swap the boss's color and call in its minions around it.
+/// Authoritative only; clients see the new phase, color and minions through
+/// replication.
+#[allow(clippy::type_complexity)]
+pub fn boss_phase_system(
+    mut bosses: Query<
+        (
+            &mut BossState,
+            &mut TeamColor,
+            &EnemyHealth,
+            &Archetype,
+            &Position,
+            &Radius,
+            &InRoom,
+        ),
+        Without<Ship>,
+    >,
+    ships: Query<(&Position, &Health, &InRoom), (With<Ship>, Without<Parked>)>,
+    mut rngs: Query<(&mut GameRng, Option<&RoomName>)>,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    bounds: Res<ArenaBounds>,
+    mut commands: Commands,
+) {
+    for (mut state, mut color, health, archetype, pos, radius, room) in bosses.iter_mut() {
+        if !rooms.playing(Some(room)) {
+            continue;
+        }
+        let Some(phases) = phases(&registry, archetype) else {
+            continue;
+        };
+        let Some(reached) = phases
+            .iter()
+            .rposition(|phase| health.fraction() <= phase.below)
+        else {
+            continue;
+        };
+        if reached <= state.phase {
+            continue;
+        }
+        let Ok((mut rng, name)) = rngs.get_mut(room.0) else {
+            continue;
+        };
+        let living: Vec<Vec2> = ships
+            .iter()
+            .filter(|(_, ship_health, in_room)| ship_health.alive && in_room.0 == room.0)
+            .map(|(ship_pos, ..)| ship_pos.0)
+            .collect();
+
+        for phase in &phases[state.phase + 1..=reached] {
+            if phase.flip_color {
+                *color = color.opposite();
+            }
+            if let Some(minions) = &phase.minions {
+                let group = SpawnGroup {
+                    archetype: minions.archetype.clone(),
+                    count: minions.count,
+                    color: GroupColor::Random,
+                    formation: Formation::Cluster {
+                        at: pos.0,
+                        spread: radius.0 * 2.0,
+                    },
+                    delay: 0.0,
+                };
+                spawn_group(
+                    &mut commands,
+                    room.0,
+                    &group,
+                    1.0,
+                    &living,
+                    &registry,
+                    &bounds,
+                    &mut rng,
+                );
+            }
+        }
+        info!(
+            "Room {}: boss {} enters phase {} at {}/{} hit points",
+            name.map_or("local", |name| name.0.as_str()),
+            archetype.0,
+            reached + 1,
+            health.current,
+            health.max
+        );
+        state.phase = reached;
+    }
+}
@@ -1,4 +1,6 @@
 use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use serde::{Deserialize, Serialize};
 
 #[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
@@ -7,6 +9,18 @@ pub enum TeamColor {
     Blue,
 }
 
+impl std::str::FromStr for TeamColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "red" => Ok(TeamColor::Red),
+            "blue" => Ok(TeamColor::Blue),
+            _ => Err(format!("unknown team color {s:?}, expected red or blue")),
+        }
+    }
+}
+
 impl TeamColor {
     pub fn opposite(self) -> Self {
         match self {
@@ -61,8 +75,94 @@ pub enum EnemyType {
     Drifter,
     Chaser,
     Orbiter,
+    /// Drifts like a Drifter; a same-color kill splits it into two smaller
+    /// drifters flying apart, each possibly of the opposite color.
+    Splitter,
+    /// Turns toward the nearest ship behind a shield on its facing side.
+    /// Only a same-color hit from behind destroys it; the front bounces ships.
+    Shielder,
+    /// Drifts about and charges at ships, soaking up hits through phases
+    /// that can swap its color and call in minions.
+    Boss,
+}
+
+impl EnemyType {
+    /// Base points for destroying the built-in archetype of this kind,
+    /// before the combo multiplier. The `EnemyRegistry` has the final say.
+    pub fn points(self) -> u32 {
+        match self {
+            EnemyType::Drifter => 10,
+            EnemyType::Chaser => 25,
+            EnemyType::Orbiter => 20,
+            EnemyType::Splitter => 30,
+            EnemyType::Shielder => 50,
+            EnemyType::Boss => 500,
+        }
+    }
+}
+
+/// Name of the `EnemyArchetype` an enemy was spawned as. Systems look its
+/// stats up in the `EnemyRegistry`.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash, Reflect)]
+pub struct Archetype(pub String);
+
+/// Multiplier on an enemy's archetype speed, set when the difficulty
+/// director has sped its wave up or slowed it down. Absent means 1.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct SpeedScale(pub f32);
+
+/// Hits an enemy can take before it is destroyed, for archetypes with more
+/// than one. Same-color hits chip one off; ships that touch it bounce clear.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct EnemyHealth {
+    pub current: u32,
+    pub max: u32,
+    /// Seconds left during which further hits glance off.
+    pub cooldown: f32,
+}
+
+impl EnemyHealth {
+    pub fn new(max: u32) -> Self {
+        Self {
+            current: max,
+            max,
+            cooldown: 0.0,
+        }
+    }
+
+    /// Fraction of its hit points left.
+    pub fn fraction(&self) -> f32 {
+        self.current as f32 / self.max.max(1) as f32
+    }
+
+    /// Take one hit unless still recovering from the last. Returns whether
+    /// that was its last hit point.
+    pub fn hit(&mut self) -> bool {
+        if self.cooldown > 0.0 {
+            return false;
+        }
+        self.current = self.current.saturating_sub(1);
+        self.cooldown = ENEMY_HIT_COOLDOWN;
+        self.current == 0
+    }
 }
 
+/// Where a boss is in its fight. Replicated so clients can show it.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub struct BossState {
+    /// Index into its archetype's phases.
+    pub phase: usize,
+    /// Seconds until its next charge.
+    pub charge_timer: f32,
+    /// Seconds left in the charge it is on, zero when cruising.
+    pub charging: f32,
+}
+
+/// Seconds until a newly spawned enemy arrives. Until then it only marks
+/// where it will appear: it holds still, can't be hit and hurts no one.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct SpawnWarning(pub f32);
+
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect, Deref, DerefMut)]
 pub struct Velocity(pub Vec2);
 
@@ -74,10 +174,78 @@ pub struct OrbitData {
     pub speed: f32,
 }
 
+/// Heading in radians, counter-clockwise from +X.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct Facing(pub f32);
+
+impl Facing {
+    pub fn direction(self) -> Vec2 {
+        Vec2::from_angle(self.0)
+    }
+}
+
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
 pub struct OwnedBy(pub u64);
 
-#[derive(Resource, Clone, Debug, Default)]
+/// A ship whose client dropped out mid-match. It holds still and can't be
+/// hit, keeping its color and score, until the client reconnects or a new
+/// player takes over its seat.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct Parked;
+
+/// A ship's running score. Awarded by the server, replicated to clients.
+/// Each kill within `COMBO_WINDOW` of the last raises the multiplier;
+/// it lapses when the window runs out or the ship dies.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Reflect)]
+pub struct Score {
+    pub points: u32,
+    pub kills: u32,
+    pub combo: u32,
+    pub combo_timer: f32,
+}
+
+impl Score {
+    /// Multiplier applied to the next kill.
+    pub fn multiplier(&self) -> u32 {
+        (self.combo + 1).min(MAX_COMBO)
+    }
+}
+
+/// A pickup waiting in the arena. Either ship collects it on contact.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum PowerUp {
+    ElectricRope,
+}
+
+/// Active electric rope: while it lasts, a beam between the Red and Blue
+/// ships destroys enemies of either color that touch it, bosses aside.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub struct ElectricRope {
+    pub remaining: f32,
+}
+
+/// A ship's gun. Fires along the ship's aim whenever the cooldown has run
+/// out; each shot starts it again. Predicted, so the firing client shoots
+/// on the same ticks as the server.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Reflect)]
+pub struct Weapon {
+    pub cooldown: f32,
+}
+
+/// A shot in flight. It only destroys enemies of its own color; any other
+/// enemy, or a shield, soaks it up.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub struct Projectile {
+    pub remaining: f32,
+}
+
+/// The ship that fired a projectile, credited with its kills. Never
+/// replicated: clients only know it on the shots they pre-spawn.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FiredBy(pub Entity);
+
+/// Wave progress for one match, kept on its match entity.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Reflect)]
 pub struct WaveState {
     pub current_wave: u32,
     pub enemies_remaining: u32,
@@ -85,6 +253,162 @@ pub struct WaveState {
     pub active: bool,
 }
 
+/// Seed for the current match. The server puts it on the replicated match
+/// entity so every peer seeds an identical `GameRng` there.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct MatchSeed(pub u64);
+
+/// Where the match is in its lifecycle. The server drives it on the match
+/// entity (alongside `MatchSeed`); the shared simulation only runs a room's
+/// entities while its match is `Playing`.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub enum MatchState {
+    /// Waiting for the room to fill up and everyone to be ready.
+    #[default]
+    Lobby,
+    /// Everyone is here; play starts when the countdown runs out.
+    Countdown {
+        remaining: f32,
+    },
+    Playing,
+    /// The team ran out of lives. Waits for a rematch request.
+    GameOver,
+}
+
+/// How ships respond to steering in a match, kept on its match entity
+/// (replicated, so clients predict with the same model).
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub enum ShipHandling {
+    /// Ships move at `SHIP_SPEED` the moment they're steered and stop dead
+    /// when let go.
+    #[default]
+    Direct,
+    /// Steering accelerates the ship's `Velocity`, and friction bleeds it off.
+    Momentum(Momentum),
+}
+
+/// Tuning for `ShipHandling::Momentum`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct Momentum {
+    /// Units per second gained each second at full stick.
+    pub acceleration: f32,
+    pub max_speed: f32,
+    /// Share of its velocity a ship loses per second, as a decay rate:
+    /// higher stops sooner.
+    pub friction: f32,
+    pub walls: WallBehaviour,
+}
+
+impl Default for Momentum {
+    fn default() -> Self {
+        Self {
+            acceleration: SHIP_ACCELERATION,
+            max_speed: SHIP_SPEED,
+            friction: SHIP_FRICTION,
+            walls: WallBehaviour::Slide,
+        }
+    }
+}
+
+/// What a ship with momentum does when it runs into the arena wall.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum WallBehaviour {
+    /// Lose all velocity.
+    Stop,
+    /// Lose only the velocity into the wall and keep moving along it.
+    #[default]
+    Slide,
+}
+
+/// Lives shared by the whole team. Each ship death spends one; the match is
+/// over when none are left.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct TeamLives(pub u32);
+
+/// The room an authoritative entity plays in: the match entity holding its
+/// `MatchState`. Everything the server or local sim spawns carries one, so
+/// several matches can share a world without touching. Clients only see
+/// their own room and never receive it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InRoom(pub Entity);
+
+/// Name clients use to find a room, kept on its match entity.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Reflect)]
+pub struct RoomName(pub String);
+
+/// A client seated in a room, with the color it flies. A player who drops
+/// out mid-match stays on the roster, disconnected, so it can come back.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub struct RoomPlayer {
+    pub client: u64,
+    pub color: TeamColor,
+    pub ready: bool,
+    pub connected: bool,
+}
+
+/// Everyone seated in a room, kept on its match entity. A room seats one
+/// player per color; a client flying both ships (solo or couch co-op)
+/// holds both seats.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Reflect)]
+pub struct RoomPlayers(pub Vec<RoomPlayer>);
+
+impl RoomPlayers {
+    /// A color nobody in the room has taken yet, Red first.
+    pub fn free_color(&self) -> Option<TeamColor> {
+        [TeamColor::Red, TeamColor::Blue]
+            .into_iter()
+            .find(|color| !self.has_color(*color))
+    }
+
+    /// Whether a connected player in the room already flies `color`. A
+    /// disconnected player's color is up for grabs.
+    pub fn has_color(&self, color: TeamColor) -> bool {
+        self.0
+            .iter()
+            .any(|player| player.connected && player.color == color)
+    }
+
+    /// The seats `client` holds: one, or both when flying both ships.
+    pub fn of_client_mut(&mut self, client: u64) -> impl Iterator<Item = &mut RoomPlayer> {
+        self.0
+            .iter_mut()
+            .filter(move |player| player.client == client)
+    }
+
+    pub fn all_ready(&self) -> bool {
+        self.0.iter().all(|player| player.ready)
+    }
+}
+
+/// Deterministic RNG for all gameplay randomness (wave composition, spawn points).
+/// Identical seeds yield identical waves. Each match entity has its own,
+/// seeded from its `MatchSeed`.
+#[derive(Component, Clone, Debug, Deref, DerefMut)]
+pub struct GameRng {
+    seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
 #[derive(Resource, Clone, Debug)]
 pub struct ArenaBounds {
     pub half_width: f32,
@@ -101,7 +425,12 @@ impl Default for ArenaBounds {
 }
 
 pub const SHIP_SPEED: f32 = 300.0;
+/// Default `Momentum::acceleration`: full speed in about a fifth of a second.
+pub const SHIP_ACCELERATION: f32 = 1800.0;
+/// Default `Momentum::friction`.
+pub const SHIP_FRICTION: f32 = 4.0;
 pub const SHIP_RADIUS: f32 = 15.0;
+// Built-in enemy archetypes, used when there is no enemies file
 pub const ENEMY_RADIUS: f32 = 10.0;
 pub const DRIFTER_SPEED: f32 = 120.0;
 pub const CHASER_SPEED: f32 = 150.0;
@@ -110,3 +439,48 @@ pub const ORBITER_RADIUS: f32 = 80.0;
 pub const RESPAWN_TIME: f32 = 2.0;
 pub const INVULNERABLE_TIME: f32 = 1.0;
 pub const WAVE_BREATHER: f32 = 3.0;
+/// Every this many waves, a procedural wave is a boss fight instead
+pub const BOSS_WAVE_INTERVAL: u32 = 5;
+pub const BOSS_RADIUS: f32 = 40.0;
+pub const BOSS_SPEED: f32 = 60.0;
+pub const BOSS_HIT_POINTS: u32 = 30;
+/// A charging boss moves this many times its usual speed...
+pub const BOSS_CHARGE_SPEEDUP: f32 = 4.0;
+/// ...for this many seconds
+pub const BOSS_CHARGE_TIME: f32 = 0.6;
+/// How far a ship that chips a tough enemy by touching it is pushed clear
+pub const ENEMY_HIT_KNOCKBACK: f32 = 30.0;
+/// Seconds a tough enemy shrugs off further hits after taking one
+pub const ENEMY_HIT_COOLDOWN: f32 = 0.1;
+/// Seconds a wave's enemy is telegraphed before it arrives
+pub const SPAWN_TELEGRAPH: f32 = 1.0;
+/// Procedural waves arrive this many enemies at a time...
+pub const SPAWN_BATCH_SIZE: usize = 4;
+/// ...this many seconds apart
+pub const SPAWN_BATCH_INTERVAL: f32 = 1.5;
+/// No wave enemy appears closer than this to a living ship
+pub const SPAWN_SAFE_DISTANCE: f32 = 200.0;
+pub const SPLITTER_RADIUS: f32 = 14.0;
+pub const SPLITTER_SPEED: f32 = 90.0;
+pub const SPLITTER_CHILD_RADIUS: f32 = 6.0;
+pub const SPLITTER_CHILD_SPEED: f32 = 200.0;
+pub const SHIELDER_RADIUS: f32 = 14.0;
+pub const SHIELDER_SPEED: f32 = 70.0;
+/// Radians per second a shielder can turn toward its target.
+pub const SHIELDER_TURN_RATE: f32 = 1.5;
+/// Extra distance a ship is pushed clear of a shield it touches.
+pub const SHIELDER_KNOCKBACK: f32 = 20.0;
+/// Seconds after a kill during which the next one extends the combo.
+pub const COMBO_WINDOW: f32 = 2.0;
+pub const MAX_COMBO: u32 = 8;
+pub const POWERUP_RADIUS: f32 = 12.0;
+pub const ROPE_DURATION: f32 = 8.0;
+pub const ROPE_HALF_WIDTH: f32 = 3.0;
+/// A rope pickup spawns with every wave divisible by this.
+pub const ROPE_WAVE_INTERVAL: u32 = 3;
+/// Seconds between shots while a ship keeps aiming.
+pub const FIRE_COOLDOWN: f32 = 0.15;
+pub const PROJECTILE_SPEED: f32 = 700.0;
+pub const PROJECTILE_RADIUS: f32 = 4.0;
+/// Seconds a shot flies before fizzling out, if it hasn't left the arena.
+pub const PROJECTILE_LIFETIME: f32 = 1.5;
@@ -0,0 +1,263 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::components::*;
+use crate::scoring::EnemyKilled;
+use crate::systems::ShipKilled;
+
+/// How hard procedural waves are pushed, as multipliers on the base wave:
+/// 1.0 everywhere is the game as designed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Difficulty {
+    /// Scales how many enemies a procedural wave has.
+    pub enemy_count: f32,
+    /// Scales the speed of every enemy spawned.
+    pub speed: f32,
+    /// Scales the odds of chaser archetypes in procedural waves.
+    pub chaser_share: f32,
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self {
+            enemy_count: 1.0,
+            speed: 1.0,
+            chaser_share: 1.0,
+        }
+    }
+}
+
+impl Difficulty {
+    fn step(self, by: f32, config: &DirectorConfig) -> Self {
+        Self {
+            enemy_count: (self.enemy_count + by)
+                .clamp(config.min.enemy_count, config.max.enemy_count),
+            speed: (self.speed + by).clamp(config.min.speed, config.max.speed),
+            chaser_share: (self.chaser_share + by)
+                .clamp(config.min.chaser_share, config.max.chaser_share),
+        }
+    }
+}
+
+/// When the director steps in, and how far it may go.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirectorConfig {
+    /// Off, every match plays at the base `Difficulty`.
+    pub enabled: bool,
+    /// Lowest the director may ease each multiplier to.
+    pub min: Difficulty,
+    /// Highest the director may push each multiplier to.
+    pub max: Difficulty,
+    /// How far each multiplier moves after a wave.
+    pub step: f32,
+    /// Ship deaths in one wave that mark the team as struggling.
+    pub struggling_deaths: u32,
+    /// Seconds per enemy beyond which a clear counts as slow.
+    pub slow_clear: f32,
+    /// Seconds per enemy under which a clear counts as fast.
+    pub fast_clear: f32,
+    /// Kills per second each color needs for a fast clear to count; a team
+    /// leaning on one color isn't cruising.
+    pub min_kill_rate: f32,
+}
+
+impl Default for DirectorConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min: Difficulty {
+                enemy_count: 0.6,
+                speed: 0.75,
+                chaser_share: 0.5,
+            },
+            max: Difficulty {
+                enemy_count: 1.5,
+                speed: 1.3,
+                chaser_share: 1.5,
+            },
+            step: 0.1,
+            struggling_deaths: 1,
+            slow_clear: 3.0,
+            fast_clear: 1.5,
+            min_kill_rate: 0.2,
+        }
+    }
+}
+
+/// How the team did in one wave.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WaveReport {
+    pub wave: u32,
+    pub enemies: u32,
+    pub deaths: u32,
+    pub time_to_clear: f32,
+    pub red_kills: u32,
+    pub blue_kills: u32,
+}
+
+impl WaveReport {
+    /// Kills per second by ships of `color`.
+    pub fn kill_rate(&self, color: TeamColor) -> f32 {
+        let kills = match color {
+            TeamColor::Red => self.red_kills,
+            TeamColor::Blue => self.blue_kills,
+        };
+        kills as f32 / self.time_to_clear.max(f32::EPSILON)
+    }
+}
+
+/// The director's read on a finished wave.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// Deaths or a slow clear: ease off.
+    Struggling,
+    Steady,
+    /// No deaths and a fast clear by both colors: push harder.
+    Cruising,
+}
+
+/// How many of its latest decisions the director keeps.
+pub const DECISIONS_KEPT: usize = 64;
+
+/// One call the director made, after wave `report.wave` of `room`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DirectorDecision {
+    pub room: Entity,
+    pub report: WaveReport,
+    pub verdict: Verdict,
+    /// Difficulty for the waves that follow.
+    pub difficulty: Difficulty,
+}
+
+/// Adapts each match's procedural waves to how its team is doing. Reviews
+/// every cleared wave and eases off or pushes harder within the configured
+/// bounds. Authoritative only; its decisions are kept for inspection.
+#[derive(Resource, Debug, Default)]
+pub struct Director {
+    pub config: DirectorConfig,
+    rooms: HashMap<Entity, RoomDirector>,
+    /// The last `DECISIONS_KEPT` decisions made, across rooms, oldest
+    /// first.
+    pub decisions: VecDeque<DirectorDecision>,
+}
+
+#[derive(Debug, Default)]
+struct RoomDirector {
+    difficulty: Difficulty,
+    report: WaveReport,
+}
+
+impl Director {
+    pub fn new(config: DirectorConfig) -> Self {
+        Self {
+            config,
+            ..default()
+        }
+    }
+
+    /// The difficulty `room`'s next waves spawn at.
+    pub fn difficulty(&self, room: Entity) -> Difficulty {
+        self.rooms
+            .get(&room)
+            .map_or_else(Difficulty::default, |room| room.difficulty)
+    }
+
+    /// Start tracking wave `wave` of `room`.
+    pub fn wave_started(&mut self, room: Entity, wave: u32) {
+        self.rooms.entry(room).or_default().report = WaveReport { wave, ..default() };
+    }
+
+    /// Count `enemies` more spawned into `room`'s wave.
+    pub fn spawned(&mut self, room: Entity, enemies: u32) {
+        self.rooms.entry(room).or_default().report.enemies += enemies;
+    }
+
+    /// Let `dt` seconds of `room`'s wave pass.
+    pub fn tick(&mut self, room: Entity, dt: f32) {
+        self.rooms.entry(room).or_default().report.time_to_clear += dt;
+    }
+
+    /// Review `room`'s cleared wave and set the difficulty of the next ones.
+    pub fn wave_cleared(&mut self, room: Entity, name: &str) {
+        let config = self.config;
+        let state = self.rooms.entry(room).or_default();
+        let report = std::mem::take(&mut state.report);
+        if !config.enabled {
+            return;
+        }
+
+        let per_enemy = report.time_to_clear / report.enemies.max(1) as f32;
+        let verdict = if report.deaths >= config.struggling_deaths || per_enemy > config.slow_clear
+        {
+            Verdict::Struggling
+        } else if report.deaths == 0
+            && per_enemy < config.fast_clear
+            && report.kill_rate(TeamColor::Red) >= config.min_kill_rate
+            && report.kill_rate(TeamColor::Blue) >= config.min_kill_rate
+        {
+            Verdict::Cruising
+        } else {
+            Verdict::Steady
+        };
+        state.difficulty = match verdict {
+            Verdict::Struggling => state.difficulty.step(-config.step, &config),
+            Verdict::Steady => state.difficulty,
+            Verdict::Cruising => state.difficulty.step(config.step, &config),
+        };
+
+        info!(
+            "Room {}: wave {} {:?} ({} deaths, {:.1}s for {} enemies, red {:.2}/s, blue {:.2}/s), difficulty now {:?}",
+            name,
+            report.wave,
+            verdict,
+            report.deaths,
+            report.time_to_clear,
+            report.enemies,
+            report.kill_rate(TeamColor::Red),
+            report.kill_rate(TeamColor::Blue),
+            state.difficulty
+        );
+        if self.decisions.len() == DECISIONS_KEPT {
+            self.decisions.pop_front();
+        }
+        self.decisions.push_back(DirectorDecision {
+            room,
+            report,
+            verdict,
+            difficulty: state.difficulty,
+        });
+    }
+
+    /// Forget `room`'s history, for a fresh match or once it's closed.
+    pub fn reset(&mut self, room: Entity) {
+        self.rooms.remove(&room);
+    }
+}
+
+/// Count a ship death against its room's current wave.
+pub fn track_deaths(
+    trigger: On<ShipKilled>,
+    ships: Query<&InRoom>,
+    mut director: ResMut<Director>,
+) {
+    if let Ok(room) = ships.get(trigger.event().ship) {
+        director.rooms.entry(room.0).or_default().report.deaths += 1;
+    }
+}
+
+/// Count a kill toward the killing ship's color.
+pub fn track_kills(
+    trigger: On<EnemyKilled>,
+    ships: Query<(&TeamColor, &InRoom)>,
+    mut director: ResMut<Director>,
+) {
+    let Ok((color, room)) = ships.get(trigger.event().ship) else {
+        return;
+    };
+    let report = &mut director.rooms.entry(room.0).or_default().report;
+    match color {
+        TeamColor::Red => report.red_kills += 1,
+        TeamColor::Blue => report.blue_kills += 1,
+    }
+}
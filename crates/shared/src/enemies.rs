@@ -1,94 +1,231 @@
+pub mod formations;
+
 use bevy::prelude::*;
 use rand::Rng;
 
+use crate::archetypes::{Behaviour, EnemyArchetype, EnemyRegistry};
 use crate::components::*;
 
-pub fn spawn_wave(commands: &mut Commands, wave: u32, bounds: &ArenaBounds) {
-    let count = (wave * 3 + 2) as usize;
-    let mut rng = rand::rng();
+/// How far inside the walls edge spawns sit
+const EDGE_INSET: f32 = 20.0;
 
-    for _ in 0..count {
-        let color = if rng.random_bool(0.5) {
-            TeamColor::Red
-        } else {
-            TeamColor::Blue
-        };
+/// Spots tried for a wave enemy before settling for the safest one
+const PLACEMENT_TRIES: u32 = 8;
+
+/// Spawn one enemy of `archetype` at `pos`, `speed_scale` times as fast as
+/// the archetype says and set up the way its behaviour needs. Drifters,
+/// splitters and shielders set off along `heading` if given; other headings
+/// and orbits come from the room's RNG.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    room: Entity,
+    archetype: &EnemyArchetype,
+    color: TeamColor,
+    pos: Vec2,
+    heading: Option<Vec2>,
+    speed_scale: f32,
+    bounds: &ArenaBounds,
+    rng: &mut GameRng,
+) -> Entity {
+    let speed = archetype.speed * speed_scale;
+    let mut enemy = commands.spawn((
+        InRoom(room),
+        color,
+        archetype.behaviour.kind(),
+        Archetype(archetype.name.clone()),
+        Position(pos),
+        Radius(archetype.radius),
+    ));
+
+    if speed_scale != 1.0 {
+        enemy.insert(SpeedScale(speed_scale));
+    }
+    if archetype.hit_points > 1 {
+        enemy.insert(EnemyHealth::new(archetype.hit_points));
+    }
 
-        let chaser_chance = (wave as f32 * 0.1).min(0.6);
-        let orbiter_chance = 0.2;
-        let roll: f32 = rng.random();
+    match &archetype.behaviour {
+        Behaviour::Drifter | Behaviour::Splitter { .. } => {
+            let heading = heading
+                .unwrap_or_else(|| Vec2::from_angle(rng.random_range(0.0..std::f32::consts::TAU)));
+            enemy.insert(Velocity(heading * speed));
+        }
+        Behaviour::Chaser => {
+            enemy.insert(Velocity(Vec2::ZERO));
+        }
+        Behaviour::Orbiter { orbit_radius } => {
+            let orbit_center = Vec2::new(
+                rng.random_range(-bounds.half_width * 0.6..bounds.half_width * 0.6),
+                rng.random_range(-bounds.half_height * 0.6..bounds.half_height * 0.6),
+            );
+            enemy.insert((
+                Velocity(Vec2::ZERO),
+                OrbitData {
+                    center: orbit_center,
+                    radius: *orbit_radius,
+                    angle: rng.random_range(0.0..std::f32::consts::TAU),
+                    speed: speed * if rng.random_bool(0.5) { 1.0 } else { -1.0 },
+                },
+            ));
+        }
+        Behaviour::Shielder { .. } => {
+            // Enter facing the middle of the arena unless sent elsewhere
+            let facing = Facing(heading.unwrap_or(-pos).to_angle());
+            enemy.insert((Velocity(facing.direction() * speed), facing));
+        }
+        Behaviour::Boss { phases } => {
+            enemy.insert((
+                Velocity(Vec2::ZERO),
+                BossState {
+                    phase: 0,
+                    charge_timer: phases
+                        .first()
+                        .map_or(f32::INFINITY, |phase| phase.charge_every),
+                    charging: 0.0,
+                },
+            ));
+        }
+    }
 
-        let enemy_type = if roll < chaser_chance {
-            EnemyType::Chaser
-        } else if roll < chaser_chance + orbiter_chance {
-            EnemyType::Orbiter
+    enemy.id()
+}
+
+/// A splitter was destroyed by a same-color ship or shot, or the rope.
+/// Triggered by `collision_system`, `projectile_system` and `rope_system`;
+/// `away` points the way the hit was travelling, and `speed_scale` is the
+/// splitter's own (see [`SpeedScale`]).
+#[derive(Event, Clone, Debug)]
+pub struct SplitterDestroyed {
+    pub position: Vec2,
+    pub color: TeamColor,
+    pub away: Vec2,
+    pub room: Option<InRoom>,
+    pub splitter: Archetype,
+    pub speed_scale: f32,
+}
+
+/// Spawn a destroyed splitter's two children through [`spawn_enemy`], of
+/// the archetype it names and as sped up as it was, fanning out 45° either
+/// side of `away` so they leave the ship that hit it. Each child rolls for
+/// the opposite color from the room's RNG.
+/// Authoritative only (server or local sim); clients receive the children
+/// through replication.
+pub fn split_on_death(
+    trigger: On<SplitterDestroyed>,
+    mut rngs: Query<&mut GameRng>,
+    registry: Res<EnemyRegistry>,
+    bounds: Res<ArenaBounds>,
+    mut commands: Commands,
+) {
+    let event = trigger.event();
+    let Some(splitter) = registry.of(&event.splitter) else {
+        return;
+    };
+    let Behaviour::Splitter { child } = &splitter.behaviour else {
+        return;
+    };
+    let Some(child) = registry.get(child) else {
+        return;
+    };
+    let Some(room) = event.room else {
+        return;
+    };
+    let Ok(mut rng) = rngs.get_mut(room.0) else {
+        return;
+    };
+    let away = event.away.try_normalize().unwrap_or(Vec2::X);
+
+    for angle in [std::f32::consts::FRAC_PI_4, -std::f32::consts::FRAC_PI_4] {
+        let dir = Vec2::from_angle(angle).rotate(away);
+        let color = if rng.random_bool(0.5) {
+            event.color.opposite()
         } else {
-            EnemyType::Drifter
+            event.color
         };
+        spawn_enemy(
+            &mut commands,
+            room.0,
+            child,
+            color,
+            event.position + dir * splitter.radius,
+            Some(dir),
+            event.speed_scale,
+            &bounds,
+            &mut rng,
+        );
+    }
+}
 
-        let spawn_pos = random_edge_position(&mut rng, bounds);
+/// Where a wave enemy goes: the first spot `place` picks that keeps
+/// `SPAWN_SAFE_DISTANCE` from every living ship in `ships`. If a few tries
+/// all land too close, the one farthest from its nearest ship is pushed out
+/// to the safe distance, staying inside the walls.
+pub fn safe_spawn_position(
+    rng: &mut GameRng,
+    bounds: &ArenaBounds,
+    ships: &[Vec2],
+    mut place: impl FnMut(&mut GameRng) -> Vec2,
+) -> Vec2 {
+    let nearest_ship = |pos: Vec2| {
+        ships
+            .iter()
+            .copied()
+            .min_by(|a, b| a.distance(pos).total_cmp(&b.distance(pos)))
+    };
+    let clearance = |pos: Vec2| nearest_ship(pos).map_or(f32::INFINITY, |ship| ship.distance(pos));
 
-        match enemy_type {
-            EnemyType::Drifter => {
-                let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
-                let vel = Vec2::new(angle.cos(), angle.sin()) * DRIFTER_SPEED;
-                commands.spawn((
-                    color,
-                    enemy_type,
-                    Position(spawn_pos),
-                    Velocity(vel),
-                    Radius(ENEMY_RADIUS),
-                ));
-            }
-            EnemyType::Chaser => {
-                commands.spawn((
-                    color,
-                    enemy_type,
-                    Position(spawn_pos),
-                    Velocity(Vec2::ZERO),
-                    Radius(ENEMY_RADIUS),
-                ));
-            }
-            EnemyType::Orbiter => {
-                let orbit_center = Vec2::new(
-                    rng.random_range(-bounds.half_width * 0.6..bounds.half_width * 0.6),
-                    rng.random_range(-bounds.half_height * 0.6..bounds.half_height * 0.6),
-                );
-                commands.spawn((
-                    color,
-                    enemy_type,
-                    Position(spawn_pos),
-                    Velocity(Vec2::ZERO),
-                    Radius(ENEMY_RADIUS),
-                    OrbitData {
-                        center: orbit_center,
-                        radius: ORBITER_RADIUS,
-                        angle: rng.random_range(0.0..std::f32::consts::TAU),
-                        speed: ORBITER_SPEED * if rng.random_bool(0.5) { 1.0 } else { -1.0 },
-                    },
-                ));
-            }
+    let mut best = place(rng);
+    for _ in 1..PLACEMENT_TRIES {
+        if clearance(best) >= SPAWN_SAFE_DISTANCE {
+            return best;
+        }
+        let next = place(rng);
+        if clearance(next) > clearance(best) {
+            best = next;
         }
     }
+
+    let Some(ship) = nearest_ship(best) else {
+        return best;
+    };
+    if ship.distance(best) >= SPAWN_SAFE_DISTANCE {
+        return best;
+    }
+    let away = (best - ship)
+        .try_normalize()
+        .unwrap_or_else(|| (-ship).normalize_or(Vec2::X));
+    let pushed = ship + away * SPAWN_SAFE_DISTANCE;
+    Vec2::new(
+        pushed.x.clamp(
+            -bounds.half_width + EDGE_INSET,
+            bounds.half_width - EDGE_INSET,
+        ),
+        pushed.y.clamp(
+            -bounds.half_height + EDGE_INSET,
+            bounds.half_height - EDGE_INSET,
+        ),
+    )
 }
 
-fn random_edge_position(rng: &mut impl Rng, bounds: &ArenaBounds) -> Vec2 {
+/// A random spot just inside one of the arena's walls.
+pub fn random_edge_position(rng: &mut GameRng, bounds: &ArenaBounds) -> Vec2 {
     let side = rng.random_range(0..4);
     match side {
         0 => Vec2::new(
             rng.random_range(-bounds.half_width..bounds.half_width),
-            bounds.half_height - 20.0,
+            bounds.half_height - EDGE_INSET,
         ),
         1 => Vec2::new(
             rng.random_range(-bounds.half_width..bounds.half_width),
-            -bounds.half_height + 20.0,
+            -bounds.half_height + EDGE_INSET,
         ),
         2 => Vec2::new(
-            bounds.half_width - 20.0,
+            bounds.half_width - EDGE_INSET,
             rng.random_range(-bounds.half_height..bounds.half_height),
         ),
         _ => Vec2::new(
-            -bounds.half_width + 20.0,
+            -bounds.half_width + EDGE_INSET,
             rng.random_range(-bounds.half_height..bounds.half_height),
         ),
     }
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{random_edge_position, safe_spawn_position, EDGE_INSET};
+use crate::components::*;
+
+/// Gap between neighbours in a pincer's columns
+const PINCER_SPACING: f32 = 40.0;
+
+/// Where a group's enemies appear, never closer than `SPAWN_SAFE_DISTANCE`
+/// to a living ship, and which way they set off. Headings steer drifters,
+/// splitters and shielders; chasers and orbiters follow their own AI.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum Formation {
+    /// Each at a random spot along the walls, as procedural waves do.
+    #[default]
+    Edges,
+    /// Scattered up to `spread` units around `at`.
+    Cluster { at: Vec2, spread: f32 },
+    /// Evenly around a circle of `radius` about the arena's centre, all
+    /// closing in on it.
+    Ring { radius: f32 },
+    /// A line along the `from` wall, alternating Red and Blue whatever the
+    /// group's color, sweeping across to the far wall.
+    Wall { from: Side },
+    /// Two columns on opposite walls across `axis`, heading for each other.
+    Pincer { axis: Axis },
+    /// Packed within `radius` of `at`, all heading the same random way.
+    Swarm { at: Vec2, radius: f32 },
+}
+
+/// One of the arena's walls.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Side {
+    /// Unit vector from the arena's centre toward this wall.
+    pub fn outward(self) -> Vec2 {
+        match self {
+            Side::Left => Vec2::NEG_X,
+            Side::Right => Vec2::X,
+            Side::Top => Vec2::Y,
+            Side::Bottom => Vec2::NEG_Y,
+        }
+    }
+}
+
+/// Left to right, or top to bottom.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// Where one enemy of a formation goes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    pub pos: Vec2,
+    /// Unit direction it sets off in; its behaviour's usual one if `None`.
+    pub heading: Option<Vec2>,
+    /// Color the formation forces on it; the group's if `None`.
+    pub color: Option<TeamColor>,
+}
+
+impl Formation {
+    /// Lay out `count` enemies, clear of the living `ships`, drawing any
+    /// randomness from the room's RNG.
+    pub fn layout(
+        self,
+        count: u32,
+        rng: &mut GameRng,
+        bounds: &ArenaBounds,
+        ships: &[Vec2],
+    ) -> Vec<Slot> {
+        let shared_heading = match self {
+            Formation::Swarm { .. } => {
+                let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+                Some(Vec2::from_angle(angle))
+            }
+            _ => None,
+        };
+
+        (0..count)
+            .map(|index| {
+                let pos = safe_spawn_position(rng, bounds, ships, |rng| {
+                    self.position(index, count, rng, bounds)
+                });
+                Slot {
+                    pos,
+                    heading: shared_heading.or_else(|| self.heading(index, pos)),
+                    color: self.color(index),
+                }
+            })
+            .collect()
+    }
+
+    /// Where enemy `index` of `count` is meant to go, kept inside the band
+    /// just within the walls however far out the formation reaches.
+    fn position(self, index: u32, count: u32, rng: &mut GameRng, bounds: &ArenaBounds) -> Vec2 {
+        // Fraction of the way along a line, leaving equal gaps at the ends
+        let along = (index as f32 + 0.5) / count as f32;
+        let (inner_x, inner_y) = inner_extents(bounds);
+        let pos = match self {
+            Formation::Edges => random_edge_position(rng, bounds),
+            Formation::Cluster { at, spread } => {
+                let angle: f32 = rng.random_range(0.0..std::f32::consts::TAU);
+                let distance = rng.random_range(0.0..=spread.max(0.0));
+                at + Vec2::from_angle(angle) * distance
+            }
+            Formation::Ring { radius } => {
+                Vec2::from_angle(std::f32::consts::TAU * index as f32 / count as f32) * radius
+            }
+            Formation::Wall { from } => match from {
+                Side::Left | Side::Right => {
+                    Vec2::new(from.outward().x * inner_x, -inner_y + 2.0 * inner_y * along)
+                }
+                Side::Top | Side::Bottom => {
+                    Vec2::new(-inner_x + 2.0 * inner_x * along, from.outward().y * inner_y)
+                }
+            },
+            Formation::Pincer { axis } => {
+                // Even indices on one wall, odd on the other, each column
+                // centred on its wall
+                let side = if index.is_multiple_of(2) { -1.0 } else { 1.0 };
+                let column = count.div_ceil(2) - (count % 2) * (index % 2);
+                let offset = ((index / 2) as f32 - (column as f32 - 1.0) / 2.0) * PINCER_SPACING;
+                match axis {
+                    Axis::Horizontal => Vec2::new(side * inner_x, offset),
+                    Axis::Vertical => Vec2::new(offset, side * inner_y),
+                }
+            }
+            Formation::Swarm { at, radius } => {
+                // Golden-angle spiral: evenly packed however many there are
+                let golden_angle = std::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+                let distance = radius * ((index as f32 + 0.5) / count as f32).sqrt();
+                at + Vec2::from_angle(golden_angle * index as f32) * distance
+            }
+        };
+        pos.clamp(Vec2::new(-inner_x, -inner_y), Vec2::new(inner_x, inner_y))
+    }
+
+    /// Which way enemy `index`, placed at `pos`, sets off.
+    fn heading(self, index: u32, pos: Vec2) -> Option<Vec2> {
+        match self {
+            Formation::Edges | Formation::Cluster { .. } | Formation::Swarm { .. } => None,
+            Formation::Ring { .. } => (-pos).try_normalize(),
+            Formation::Wall { from } => Some(-from.outward()),
+            Formation::Pincer { axis } => {
+                let side = if index.is_multiple_of(2) { 1.0 } else { -1.0 };
+                Some(match axis {
+                    Axis::Horizontal => Vec2::X * side,
+                    Axis::Vertical => Vec2::Y * side,
+                })
+            }
+        }
+    }
+
+    /// The color enemy `index` must be, if the formation decides.
+    fn color(self, index: u32) -> Option<TeamColor> {
+        match self {
+            Formation::Wall { .. } if index.is_multiple_of(2) => Some(TeamColor::Red),
+            Formation::Wall { .. } => Some(TeamColor::Blue),
+            _ => None,
+        }
+    }
+}
+
+/// Half-width and half-height of the band enemies spawn in, just inside the
+/// walls.
+fn inner_extents(bounds: &ArenaBounds) -> (f32, f32) {
+    (
+        bounds.half_width - EDGE_INSET,
+        bounds.half_height - EDGE_INSET,
+    )
+}
@@ -1,8 +1,16 @@
+pub mod archetypes;
+pub mod boss;
 pub mod components;
+pub mod director;
 pub mod enemies;
+pub mod powerups;
 pub mod protocol;
+pub mod scoring;
 pub mod systems;
+pub mod waves;
+pub mod weapons;
 
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use protocol::ProtocolPlugin;
 
@@ -11,10 +19,127 @@ pub struct SharedPlugin;
 impl Plugin for SharedPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(ProtocolPlugin);
-        app.init_resource::<components::WaveState>();
         app.init_resource::<components::ArenaBounds>();
+        app.init_resource::<archetypes::EnemyRegistry>();
+        app.init_resource::<waves::WaveTable>();
+        app.add_observer(seed_game_rng);
+        app.add_observer(archetypes::warn_unknown_archetype);
+        app.add_observer(weapons::pre_spawn_shot);
+    }
+}
+
+/// Reseed a match's RNG whenever its seed is set (spawned on the server and
+/// replaced for each rematch, replicated to clients).
+fn seed_game_rng(
+    trigger: On<Insert, components::MatchSeed>,
+    seeds: Query<&components::MatchSeed>,
+    mut commands: Commands,
+) {
+    if let Ok(seed) = seeds.get(trigger.entity) {
+        commands
+            .entity(trigger.entity)
+            .insert(components::GameRng::new(seed.0));
+    }
+}
+
+/// System set for the shared gameplay chain run in `FixedUpdate`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SimulationSystems;
+
+/// Runs the shared gameplay systems every fixed tick.
+/// The server runs them authoritatively; clients run them to predict.
+pub struct SimulationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                systems::spawn_warning_system,
+                systems::enemy_health_system,
+                systems::ship_movement,
+                systems::enemy_movement,
+                systems::chaser_ai,
+                systems::shielder_ai,
+                boss::boss_ai,
+                weapons::projectile_system,
+                systems::collision_system,
+                systems::rope_system,
+                systems::respawn_system,
+                weapons::fire_system,
+            )
+                .chain()
+                .in_set(SimulationSystems),
+        );
+    }
+}
+
+/// Which rooms are in play. Systems skip entities whose match isn't
+/// `Playing`, so one room's lobby or game over leaves the others running.
+#[derive(SystemParam)]
+pub struct Rooms<'w, 's> {
+    states: Query<'w, 's, &'static components::MatchState>,
+    handling: Query<'w, 's, &'static components::ShipHandling>,
+}
+
+impl Rooms<'_, '_> {
+    /// Whether entities in `room` are simulated this tick. Entities without
+    /// a room (client copies) follow the one match their client can see.
+    pub fn playing(&self, room: Option<&components::InRoom>) -> bool {
+        let state = match room {
+            Some(room) => self.states.get(room.0).ok(),
+            None => self.states.single().ok(),
+        };
+        state == Some(&components::MatchState::Playing)
+    }
+
+    /// How ships in `room` handle; `Direct` unless the match says otherwise.
+    pub fn handling(&self, room: Option<&components::InRoom>) -> components::ShipHandling {
+        let handling = match room {
+            Some(room) => self.handling.get(room.0).ok(),
+            None => self.handling.single().ok(),
+        };
+        handling.copied().unwrap_or_default()
+    }
+}
+
+/// Gameplay only the authoritative world runs (the server, or the local
+/// test sim): anything that spawns entities or awards points, bar the
+/// shots clients pre-spawn. Clients learn the outcome through replication.
+pub struct AuthorityPlugin;
+
+impl Plugin for AuthorityPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(enemies::split_on_death);
+        app.add_observer(scoring::award_kill);
+        app.add_systems(
+            FixedUpdate,
+            (
+                systems::powerup_pickup_system,
+                boss::boss_phase_system,
+                scoring::combo_system,
+            )
+                .after(SimulationSystems),
+        );
     }
 }
 
 pub const TICK_DURATION: std::time::Duration = std::time::Duration::from_millis(16);
 pub const SERVER_PORT: u16 = 5555;
+
+/// Slowest and fastest simulation rates `--tick-rate` accepts, in Hz
+pub const MIN_TICK_RATE: f64 = 1.0;
+pub const MAX_TICK_RATE: f64 = 1000.0;
+
+/// Parse a `--tick-rate` argument: a rate in Hz between `MIN_TICK_RATE` and
+/// `MAX_TICK_RATE`, so the tick duration it gives is always valid.
+pub fn parse_tick_rate(arg: &str) -> Result<f64, String> {
+    let hz: f64 = arg.parse().map_err(|err| format!("{err}"))?;
+    if (MIN_TICK_RATE..=MAX_TICK_RATE).contains(&hz) {
+        Ok(hz)
+    } else {
+        Err(format!(
+            "must be between {MIN_TICK_RATE} and {MAX_TICK_RATE} Hz"
+        ))
+    }
+}
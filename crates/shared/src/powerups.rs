@@ -0,0 +1,22 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::components::*;
+
+/// Spawn a power-up pickup in `room`, somewhere in the inner arena clear of
+/// the walls.
+pub fn spawn_powerup(
+    commands: &mut Commands,
+    room: Entity,
+    powerup: PowerUp,
+    bounds: &ArenaBounds,
+    rng: &mut GameRng,
+) -> Entity {
+    let pos = Vec2::new(
+        rng.random_range(-bounds.half_width * 0.5..bounds.half_width * 0.5),
+        rng.random_range(-bounds.half_height * 0.5..bounds.half_height * 0.5),
+    );
+    commands
+        .spawn((InRoom(room), powerup, Position(pos), Radius(POWERUP_RADIUS)))
+        .id()
+}
@@ -8,12 +8,77 @@ use crate::components::*;
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq, Clone, Reflect)]
 pub struct PlayerInput {
     pub direction: Vec2,
+    /// Direction to fire in; zero holds fire.
+    pub aim: Vec2,
 }
 
 impl MapEntities for PlayerInput {
     fn map_entities<M: EntityMapper>(&mut self, _entity_mapper: &mut M) {}
 }
 
+/// Kills one ship made during a finished match.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PlayerKills {
+    pub color: TeamColor,
+    pub kills: u32,
+}
+
+/// Summary of a finished match, as kept on the server's leaderboard.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MatchResult {
+    pub wave: u32,
+    pub team_score: u32,
+    pub kills: Vec<PlayerKills>,
+    pub duration_secs: f32,
+    pub seed: u64,
+}
+
+/// Top match results, best first. Sent to each client when it connects and
+/// to everyone whenever a match ends.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LeaderboardMessage {
+    pub entries: Vec<MatchResult>,
+}
+
+/// Sent by a client after a game over to start the next match.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RequestRematch;
+
+/// Take a seat in the named room, creating it if nobody has yet. A client
+/// already in another room leaves it first. With a `color`, the seat is
+/// only taken if that color is free; without one, any free color will do.
+/// With `both_ships` the client takes both seats, flying both ships from one
+/// machine: solo "Uber" mode, or two players in couch co-op.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JoinRoom {
+    pub room: String,
+    pub color: Option<TeamColor>,
+    pub both_ships: bool,
+}
+
+/// Switch to the other color while the room is in its lobby, if it's free.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChooseColor(pub TeamColor);
+
+/// Sent back to a client whose `JoinRoom` or `ChooseColor` the server
+/// turned down. The client keeps whatever seat and color it had.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum Rejected {
+    /// Both colors in the room are taken.
+    RoomFull(String),
+    /// Someone in the room already flies this color.
+    ColorTaken(TeamColor),
+    /// Colors are fixed once the room's match has started.
+    MatchUnderway,
+}
+
+/// Mark this client ready (or not) to start its room's match.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SetReady(pub bool);
+
+/// Reliable, ordered channel for infrequent, non-gameplay messages.
+pub struct ReliableChannel;
+
 #[derive(Clone)]
 pub struct ProtocolPlugin;
 
@@ -21,22 +86,90 @@ impl Plugin for ProtocolPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(lightyear::prelude::input::native::InputPlugin::<PlayerInput>::default());
 
+        app.add_channel::<ReliableChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        })
+        .add_direction(NetworkDirection::Bidirectional);
+
+        app.register_message::<LeaderboardMessage>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        app.register_message::<RequestRematch>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<JoinRoom>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<ChooseColor>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<SetReady>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        app.register_message::<Rejected>()
+            .add_direction(NetworkDirection::ServerToClient);
+
         app.register_component::<TeamColor>().add_prediction();
 
         app.register_component::<Ship>().add_prediction();
 
         app.register_component::<OwnedBy>().add_prediction();
 
+        app.register_component::<Parked>().add_prediction();
+
         app.register_component::<Radius>().add_prediction();
 
-        app.register_component::<Position>().add_prediction();
+        // Interpolated for the partner's ship, which each client follows
+        // rather than predicts
+        app.register_component::<Position>()
+            .add_prediction()
+            .add_interpolation_with(|start: Position, end: Position, t: f32| {
+                Position(start.0.lerp(end.0, t))
+            });
 
         app.register_component::<Health>().add_prediction();
 
         app.register_component::<EnemyType>().add_prediction();
 
+        app.register_component::<Archetype>().add_prediction();
+
+        app.register_component::<SpeedScale>().add_prediction();
+
+        app.register_component::<SpawnWarning>().add_prediction();
+
+        app.register_component::<EnemyHealth>().add_prediction();
+
+        app.register_component::<BossState>().add_prediction();
+
         app.register_component::<Velocity>().add_prediction();
 
         app.register_component::<OrbitData>().add_prediction();
+
+        app.register_component::<Facing>().add_prediction();
+
+        app.register_component::<PowerUp>().add_prediction();
+
+        app.register_component::<ElectricRope>().add_prediction();
+
+        app.register_component::<Weapon>().add_prediction();
+
+        app.register_component::<Projectile>().add_prediction();
+
+        app.register_component::<Score>();
+
+        app.register_component::<MatchSeed>();
+
+        app.register_component::<ShipHandling>();
+
+        app.register_component::<MatchState>();
+
+        app.register_component::<TeamLives>();
+
+        app.register_component::<WaveState>();
+
+        app.register_component::<RoomName>();
+
+        app.register_component::<RoomPlayers>();
     }
 }
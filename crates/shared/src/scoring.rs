@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::components::*;
+use crate::Rooms;
+
+/// A ship destroyed an enemy by touching or shooting it, or the rope did on
+/// its behalf. Triggered by `collision_system`, `projectile_system` and
+/// `rope_system`.
+#[derive(Event, Clone, Debug)]
+pub struct EnemyKilled {
+    pub ship: Entity,
+    pub enemy: EnemyType,
+    /// Base points its archetype is worth.
+    pub points: u32,
+}
+
+/// Credit the ship with the enemy's points times its combo multiplier, then
+/// extend the combo. Authoritative only; clients receive `Score` through
+/// replication.
+pub fn award_kill(trigger: On<EnemyKilled>, mut scores: Query<&mut Score>) {
+    let event = trigger.event();
+    let Ok(mut score) = scores.get_mut(event.ship) else {
+        return;
+    };
+
+    score.points += event.points * score.multiplier();
+    score.kills += 1;
+    score.combo += 1;
+    score.combo_timer = COMBO_WINDOW;
+}
+
+/// Let combos lapse once their window runs out, and drop them on death.
+pub fn combo_system(
+    mut ships: Query<(&mut Score, &Health, &InRoom)>,
+    rooms: Rooms,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut score, health, room) in ships.iter_mut() {
+        if score.combo == 0 || !rooms.playing(Some(room)) {
+            continue;
+        }
+
+        score.combo_timer -= dt;
+        if !health.alive || score.combo_timer <= 0.0 {
+            score.combo = 0;
+            score.combo_timer = 0.0;
+        }
+    }
+}
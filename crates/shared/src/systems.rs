@@ -1,26 +1,119 @@
 use bevy::prelude::*;
 use lightyear::prelude::input::native::ActionState;
+use lightyear::prelude::{Interpolated, PredictionDespawnCommandsExt};
+use std::f32::consts::{PI, TAU};
 
+use crate::archetypes::{Behaviour, EnemyRegistry};
 use crate::components::*;
+use crate::enemies::SplitterDestroyed;
 use crate::protocol::PlayerInput;
+use crate::scoring::EnemyKilled;
+use crate::Rooms;
 
+/// Steer ships with their input, following their match's `ShipHandling`.
+/// A client only steers its own ships; its partner's are interpolated from
+/// the server.
+#[allow(clippy::type_complexity)]
 pub fn ship_movement(
-    mut query: Query<(&mut Position, &ActionState<PlayerInput>), With<Ship>>,
+    mut query: Query<
+        (
+            &mut Position,
+            &mut Velocity,
+            &ActionState<PlayerInput>,
+            Option<&InRoom>,
+        ),
+        (With<Ship>, Without<Parked>, Without<Interpolated>),
+    >,
+    rooms: Rooms,
     bounds: Res<ArenaBounds>,
+    time: Res<Time<Fixed>>,
 ) {
-    for (mut pos, input) in query.iter_mut() {
+    let dt = time.delta_secs();
+
+    for (mut pos, mut vel, input, room) in query.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
         let dir = input.0.direction;
-        if dir.length_squared() > 0.0 {
-            let movement = dir.normalize_or_zero() * SHIP_SPEED * (1.0 / 60.0);
-            pos.0 += movement;
-            pos.0.x = pos.0.x.clamp(
-                -bounds.half_width + SHIP_RADIUS,
-                bounds.half_width - SHIP_RADIUS,
-            );
-            pos.0.y = pos.0.y.clamp(
-                -bounds.half_height + SHIP_RADIUS,
-                bounds.half_height - SHIP_RADIUS,
-            );
+        match rooms.handling(room) {
+            ShipHandling::Direct => {
+                if dir.length_squared() > 0.0 {
+                    let movement = dir.normalize_or_zero() * SHIP_SPEED * dt;
+                    pos.0 += movement;
+                    clamp_ship(&mut pos, &bounds);
+                }
+            }
+            ShipHandling::Momentum(momentum) => {
+                // Analog input accelerates proportionally, capped at full stick
+                let thrust = dir.clamp_length_max(1.0) * momentum.acceleration;
+                vel.0 = ((vel.0 + thrust * dt) * (-momentum.friction * dt).exp())
+                    .clamp_length_max(momentum.max_speed);
+                pos.0 += vel.0 * dt;
+
+                let unclamped = pos.0;
+                clamp_ship(&mut pos, &bounds);
+                let hit_x = pos.0.x != unclamped.x;
+                let hit_y = pos.0.y != unclamped.y;
+                match momentum.walls {
+                    WallBehaviour::Stop if hit_x || hit_y => vel.0 = Vec2::ZERO,
+                    WallBehaviour::Stop => {}
+                    WallBehaviour::Slide => {
+                        if hit_x {
+                            vel.0.x = 0.0;
+                        }
+                        if hit_y {
+                            vel.0.y = 0.0;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn clamp_ship(pos: &mut Position, bounds: &ArenaBounds) {
+    pos.0.x = pos.0.x.clamp(
+        -bounds.half_width + SHIP_RADIUS,
+        bounds.half_width - SHIP_RADIUS,
+    );
+    pos.0.y = pos.0.y.clamp(
+        -bounds.half_height + SHIP_RADIUS,
+        bounds.half_height - SHIP_RADIUS,
+    );
+}
+
+/// Count telegraphed enemies down to their arrival, when they start moving
+/// and colliding.
+pub fn spawn_warning_system(
+    mut warnings: Query<(Entity, &mut SpawnWarning, Option<&InRoom>)>,
+    rooms: Rooms,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+
+    for (entity, mut warning, room) in warnings.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
+        warning.0 -= dt;
+        if warning.0 <= 0.0 {
+            commands.entity(entity).remove::<SpawnWarning>();
+        }
+    }
+}
+
+/// Let tough enemies recover from their last hit.
+pub fn enemy_health_system(
+    mut enemies: Query<(&mut EnemyHealth, Option<&InRoom>)>,
+    rooms: Rooms,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut health, room) in enemies.iter_mut() {
+        if health.cooldown > 0.0 && rooms.playing(room) {
+            health.cooldown = (health.cooldown - dt).max(0.0);
         }
     }
 }
@@ -28,101 +121,483 @@ pub fn ship_movement(
 #[allow(clippy::type_complexity)]
 pub fn enemy_movement(
     mut drifters: Query<
-        (&mut Position, &mut Velocity),
-        (With<EnemyType>, Without<OrbitData>, Without<Ship>),
+        (&mut Position, &mut Velocity, &Radius, Option<&InRoom>),
+        (
+            With<EnemyType>,
+            Without<OrbitData>,
+            Without<Ship>,
+            Without<SpawnWarning>,
+        ),
+    >,
+    mut orbiters: Query<
+        (&mut Position, &mut OrbitData, Option<&InRoom>),
+        (With<EnemyType>, Without<Ship>, Without<SpawnWarning>),
     >,
-    mut orbiters: Query<(&mut Position, &mut OrbitData), (With<EnemyType>, Without<Ship>)>,
+    rooms: Rooms,
     bounds: Res<ArenaBounds>,
+    time: Res<Time<Fixed>>,
 ) {
-    let dt = 1.0 / 60.0;
+    let dt = time.delta_secs();
 
-    for (mut pos, mut vel) in drifters.iter_mut() {
+    for (mut pos, mut vel, radius, room) in drifters.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
         pos.0 += vel.0 * dt;
 
-        if pos.0.x.abs() > bounds.half_width - ENEMY_RADIUS {
+        if pos.0.x.abs() > bounds.half_width - radius.0 {
             vel.0.x = -vel.0.x;
-            pos.0.x = pos.0.x.clamp(
-                -bounds.half_width + ENEMY_RADIUS,
-                bounds.half_width - ENEMY_RADIUS,
-            );
+            pos.0.x = pos
+                .0
+                .x
+                .clamp(-bounds.half_width + radius.0, bounds.half_width - radius.0);
         }
-        if pos.0.y.abs() > bounds.half_height - ENEMY_RADIUS {
+        if pos.0.y.abs() > bounds.half_height - radius.0 {
             vel.0.y = -vel.0.y;
             pos.0.y = pos.0.y.clamp(
-                -bounds.half_height + ENEMY_RADIUS,
-                bounds.half_height - ENEMY_RADIUS,
+                -bounds.half_height + radius.0,
+                bounds.half_height - radius.0,
             );
         }
     }
 
-    for (mut pos, mut orbit) in orbiters.iter_mut() {
+    for (mut pos, mut orbit, room) in orbiters.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
         orbit.angle += orbit.speed * dt;
         pos.0 = orbit.center + Vec2::new(orbit.angle.cos(), orbit.angle.sin()) * orbit.radius;
     }
 }
 
+#[allow(clippy::type_complexity)]
 pub fn chaser_ai(
-    mut chasers: Query<(&mut Velocity, &Position, &TeamColor, &EnemyType), Without<Ship>>,
-    ships: Query<(&Position, &TeamColor, &Health), With<Ship>>,
+    mut chasers: Query<
+        (
+            &mut Velocity,
+            &Position,
+            &TeamColor,
+            &EnemyType,
+            &Archetype,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (Without<Ship>, Without<SpawnWarning>),
+    >,
+    ships: Query<(&Position, &TeamColor, &Health, Option<&InRoom>), (With<Ship>, Without<Parked>)>,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
 ) {
-    for (mut vel, chaser_pos, chaser_color, enemy_type) in chasers.iter_mut() {
-        if *enemy_type != EnemyType::Chaser {
+    for (mut vel, chaser_pos, chaser_color, enemy_type, archetype, scale, chaser_room) in
+        chasers.iter_mut()
+    {
+        if *enemy_type != EnemyType::Chaser || !rooms.playing(chaser_room) {
             continue;
         }
+        let Some(archetype) = registry.of(archetype) else {
+            continue;
+        };
 
         let target = ships
             .iter()
-            .filter(|(_, color, health)| **color != *chaser_color && health.alive)
-            .min_by(|(pos_a, _, _), (pos_b, _, _)| {
+            .filter(|(_, color, health, room)| {
+                **color != *chaser_color && health.alive && *room == chaser_room
+            })
+            .min_by(|(pos_a, ..), (pos_b, ..)| {
                 let dist_a = pos_a.0.distance_squared(chaser_pos.0);
                 let dist_b = pos_b.0.distance_squared(chaser_pos.0);
                 dist_a.partial_cmp(&dist_b).unwrap()
             });
 
-        if let Some((target_pos, _, _)) = target {
+        if let Some((target_pos, ..)) = target {
             let dir = (target_pos.0 - chaser_pos.0).normalize_or_zero();
-            vel.0 = dir * CHASER_SPEED;
+            vel.0 = dir * archetype.speed * scale.map_or(1.0, |scale| scale.0);
+        }
+    }
+}
+
+/// Shielders turn toward the nearest living ship, of either color, at a
+/// limited rate and advance along their facing.
+#[allow(clippy::type_complexity)]
+pub fn shielder_ai(
+    mut shielders: Query<
+        (
+            &mut Facing,
+            &mut Velocity,
+            &Position,
+            &EnemyType,
+            &Archetype,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (Without<Ship>, Without<SpawnWarning>),
+    >,
+    ships: Query<(&Position, &Health, Option<&InRoom>), (With<Ship>, Without<Parked>)>,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    time: Res<Time<Fixed>>,
+) {
+    for (mut facing, mut vel, shielder_pos, enemy_type, archetype, scale, shielder_room) in
+        shielders.iter_mut()
+    {
+        if *enemy_type != EnemyType::Shielder || !rooms.playing(shielder_room) {
+            continue;
+        }
+        let Some(archetype) = registry.of(archetype) else {
+            continue;
+        };
+        let Behaviour::Shielder { turn_rate, .. } = archetype.behaviour else {
+            continue;
+        };
+        let max_turn = turn_rate * time.delta_secs();
+
+        let target = ships
+            .iter()
+            .filter(|(_, health, room)| health.alive && *room == shielder_room)
+            .map(|(pos, ..)| pos.0)
+            .min_by(|a, b| {
+                let dist_a = a.distance_squared(shielder_pos.0);
+                let dist_b = b.distance_squared(shielder_pos.0);
+                dist_a.partial_cmp(&dist_b).unwrap()
+            });
+
+        if let Some(target_pos) = target {
+            let wanted = (target_pos - shielder_pos.0).to_angle();
+            let diff = wrap_angle(wanted - facing.0);
+            facing.0 = wrap_angle(facing.0 + diff.clamp(-max_turn, max_turn));
         }
+        vel.0 = facing.direction() * archetype.speed * scale.map_or(1.0, |scale| scale.0);
     }
 }
 
+/// Wrap an angle into `[-PI, PI)`.
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + PI).rem_euclid(TAU) - PI
+}
+
+/// A ship touched an enemy of the other color and died. Triggered by
+/// `collision_system`; the server spends a team life on it.
+#[derive(Event, Clone, Debug)]
+pub struct ShipKilled {
+    pub ship: Entity,
+}
+
 #[allow(clippy::type_complexity)]
 pub fn collision_system(
-    mut ships: Query<(&Position, &TeamColor, &mut Health, &Radius), With<Ship>>,
-    enemies: Query<(Entity, &Position, &TeamColor, &Radius), (With<EnemyType>, Without<Ship>)>,
+    mut ships: Query<
+        (
+            Entity,
+            &mut Position,
+            &TeamColor,
+            &mut Health,
+            &Radius,
+            Option<&InRoom>,
+        ),
+        (With<Ship>, Without<Parked>, Without<Interpolated>),
+    >,
+    mut enemies: Query<
+        (
+            Entity,
+            &EnemyType,
+            &Archetype,
+            &Position,
+            &TeamColor,
+            &Radius,
+            Option<&Facing>,
+            Option<&mut EnemyHealth>,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (Without<Ship>, Without<SpawnWarning>),
+    >,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    bounds: Res<ArenaBounds>,
     mut commands: Commands,
 ) {
-    for (ship_pos, ship_color, mut ship_health, ship_radius) in ships.iter_mut() {
-        if !ship_health.alive {
+    let mut destroyed = Vec::new();
+
+    for (ship_entity, mut ship_pos, ship_color, mut ship_health, ship_radius, ship_room) in
+        ships.iter_mut()
+    {
+        if !ship_health.alive || !rooms.playing(ship_room) {
             continue;
         }
 
-        for (enemy_entity, enemy_pos, enemy_color, enemy_radius) in enemies.iter() {
+        for (
+            enemy_entity,
+            enemy_type,
+            archetype,
+            enemy_pos,
+            enemy_color,
+            enemy_radius,
+            facing,
+            mut enemy_health,
+            speed_scale,
+            enemy_room,
+        ) in enemies.iter_mut()
+        {
+            // Two ships touching the same enemy in one tick only destroy it once
+            if destroyed.contains(&enemy_entity) || enemy_room != ship_room {
+                continue;
+            }
+            let Some(stats) = registry.of(archetype) else {
+                continue;
+            };
+
             let dist = ship_pos.0.distance(enemy_pos.0);
             let min_dist = ship_radius.0 + enemy_radius.0;
 
             if dist < min_dist {
+                if let Some(facing) = facing {
+                    let offset = ship_pos.0 - enemy_pos.0;
+                    if facing.direction().dot(offset) > 0.0 {
+                        // Hit the shield: push the ship clear, nobody dies
+                        let knockback = match stats.behaviour {
+                            Behaviour::Shielder { knockback, .. } => knockback,
+                            _ => 0.0,
+                        };
+                        let normal = offset.try_normalize().unwrap_or(facing.direction());
+                        ship_pos.0 = enemy_pos.0 + normal * (min_dist + knockback);
+                        clamp_ship(&mut ship_pos, &bounds);
+                        continue;
+                    }
+                }
+
                 if *ship_color == *enemy_color {
-                    commands.entity(enemy_entity).despawn();
+                    if let Some(health) = enemy_health.as_mut() {
+                        // Tough enemies bounce the ship clear, a hit point down
+                        let normal = (ship_pos.0 - enemy_pos.0)
+                            .try_normalize()
+                            .unwrap_or(Vec2::X);
+                        ship_pos.0 = enemy_pos.0 + normal * (min_dist + ENEMY_HIT_KNOCKBACK);
+                        clamp_ship(&mut ship_pos, &bounds);
+                        if !health.hit() {
+                            continue;
+                        }
+                    }
+                    // Disables instead of despawning on predicting clients so a rollback can restore it
+                    commands.entity(enemy_entity).prediction_despawn();
+                    destroyed.push(enemy_entity);
+                    commands.trigger(EnemyKilled {
+                        ship: ship_entity,
+                        enemy: *enemy_type,
+                        points: stats.points,
+                    });
+                    if *enemy_type == EnemyType::Splitter {
+                        commands.trigger(SplitterDestroyed {
+                            position: enemy_pos.0,
+                            color: *enemy_color,
+                            away: enemy_pos.0 - ship_pos.0,
+                            room: enemy_room.copied(),
+                            splitter: archetype.clone(),
+                            speed_scale: speed_scale.map_or(1.0, |scale| scale.0),
+                        });
+                    }
                 } else if ship_health.invulnerable_timer <= 0.0 {
                     ship_health.alive = false;
                     ship_health.respawn_timer = RESPAWN_TIME;
+                    commands.trigger(ShipKilled { ship: ship_entity });
                 }
             }
         }
     }
 }
 
-pub fn respawn_system(mut ships: Query<(&mut Health, &mut Position), With<Ship>>) {
-    let dt = 1.0 / 60.0;
+/// Ticks each room's electric rope down and destroys every enemy, of either
+/// color, touching the beam between that room's Red and Blue ships; tough
+/// enemies lose a hit point instead. A kill is credited to the ship of the
+/// enemy's color, and splitters split away from the beam. Bosses shrug it
+/// off, so a fight can't be won from a distance regardless of color. The
+/// beam is down while either ship is dead.
+#[allow(clippy::type_complexity)]
+pub fn rope_system(
+    mut ropes: Query<(Entity, &mut ElectricRope, Option<&InRoom>)>,
+    ships: Query<(Entity, &Position, &TeamColor, &Health, Option<&InRoom>), With<Ship>>,
+    mut enemies: Query<
+        (
+            Entity,
+            &EnemyType,
+            &Archetype,
+            &Position,
+            &TeamColor,
+            &Radius,
+            Option<&mut EnemyHealth>,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (
+            With<EnemyType>,
+            Without<Ship>,
+            Without<SpawnWarning>,
+            Without<BossState>,
+        ),
+    >,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    for (rope_entity, mut rope, rope_room) in ropes.iter_mut() {
+        if !rooms.playing(rope_room) {
+            continue;
+        }
+
+        rope.remaining -= time.delta_secs();
+        if rope.remaining <= 0.0 {
+            commands.entity(rope_entity).prediction_despawn();
+            continue;
+        }
+
+        let endpoint = |color: TeamColor| {
+            ships
+                .iter()
+                .find(|(_, _, ship_color, health, room)| {
+                    **ship_color == color && health.alive && *room == rope_room
+                })
+                .map(|(ship, pos, ..)| (ship, pos.0))
+        };
+        let (Some((red_ship, red)), Some((blue_ship, blue))) =
+            (endpoint(TeamColor::Red), endpoint(TeamColor::Blue))
+        else {
+            continue;
+        };
+
+        for (
+            enemy_entity,
+            enemy_type,
+            archetype,
+            enemy_pos,
+            enemy_color,
+            enemy_radius,
+            enemy_health,
+            speed_scale,
+            enemy_room,
+        ) in enemies.iter_mut()
+        {
+            let touching = enemy_room == rope_room
+                && segment_intersects_circle(
+                    red,
+                    blue,
+                    enemy_pos.0,
+                    enemy_radius.0 + ROPE_HALF_WIDTH,
+                );
+            // Tough enemies take one hit per cooldown from the beam
+            if !touching || enemy_health.is_some_and(|mut health| !health.hit()) {
+                continue;
+            }
+
+            commands.entity(enemy_entity).prediction_despawn();
+            commands.trigger(EnemyKilled {
+                ship: match enemy_color {
+                    TeamColor::Red => red_ship,
+                    TeamColor::Blue => blue_ship,
+                },
+                enemy: *enemy_type,
+                points: registry.of(archetype).map_or(0, |stats| stats.points),
+            });
+            if *enemy_type == EnemyType::Splitter {
+                // Children fly off the beam, on the side the splitter was
+                let closest = closest_point_on_segment(red, blue, enemy_pos.0);
+                commands.trigger(SplitterDestroyed {
+                    position: enemy_pos.0,
+                    color: *enemy_color,
+                    away: enemy_pos.0 - closest,
+                    room: enemy_room.copied(),
+                    splitter: archetype.clone(),
+                    speed_scale: speed_scale.map_or(1.0, |scale| scale.0),
+                });
+            }
+        }
+    }
+}
+
+/// Whether the segment `a`-`b` passes within `radius` of `center`.
+pub fn segment_intersects_circle(a: Vec2, b: Vec2, center: Vec2, radius: f32) -> bool {
+    closest_point_on_segment(a, b, center).distance_squared(center) < radius * radius
+}
+
+/// The point of the segment `a`-`b` nearest to `point`.
+fn closest_point_on_segment(a: Vec2, b: Vec2, point: Vec2) -> Vec2 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    let t = if len_sq > 0.0 {
+        ((point - a).dot(ab) / len_sq).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    a + ab * t
+}
+
+/// Ships collect power-ups in their room on contact. Authoritative only
+/// (server or local sim); clients learn about the pickup through replication.
+pub fn powerup_pickup_system(
+    pickups: Query<(Entity, &PowerUp, &Position, &Radius, &InRoom)>,
+    ships: Query<(&Position, &Radius, &Health, &InRoom), With<Ship>>,
+    mut ropes: Query<(&mut ElectricRope, &InRoom)>,
+    rooms: Rooms,
+    mut commands: Commands,
+) {
+    for (pickup_entity, powerup, pickup_pos, pickup_radius, room) in pickups.iter() {
+        if !rooms.playing(Some(room)) {
+            continue;
+        }
+
+        let collected = ships
+            .iter()
+            .any(|(ship_pos, ship_radius, health, ship_room)| {
+                health.alive
+                    && ship_room == room
+                    && ship_pos.0.distance(pickup_pos.0) < ship_radius.0 + pickup_radius.0
+            });
+        if !collected {
+            continue;
+        }
+
+        commands.entity(pickup_entity).despawn();
+        match powerup {
+            PowerUp::ElectricRope => {
+                // Collecting another rope while one is active refreshes it
+                if let Some((mut rope, _)) =
+                    ropes.iter_mut().find(|(_, rope_room)| *rope_room == room)
+                {
+                    rope.remaining = ROPE_DURATION;
+                } else {
+                    commands.spawn((
+                        ElectricRope {
+                            remaining: ROPE_DURATION,
+                        },
+                        *room,
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn respawn_system(
+    mut ships: Query<
+        (&mut Health, &mut Position, &mut Velocity, Option<&InRoom>),
+        (With<Ship>, Without<Interpolated>),
+    >,
+    rooms: Rooms,
+    time: Res<Time<Fixed>>,
+) {
+    let dt = time.delta_secs();
+
+    for (mut health, mut pos, mut vel, room) in ships.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
 
-    for (mut health, mut pos) in ships.iter_mut() {
         if !health.alive {
             health.respawn_timer -= dt;
             if health.respawn_timer <= 0.0 {
                 health.alive = true;
                 health.invulnerable_timer = INVULNERABLE_TIME;
                 pos.0 = Vec2::ZERO;
+                vel.0 = Vec2::ZERO;
             }
         }
 
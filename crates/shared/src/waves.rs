@@ -0,0 +1,432 @@
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::Path;
+
+use crate::archetypes::EnemyRegistry;
+use crate::components::*;
+use crate::director::{self, Difficulty, Director, DirectorConfig};
+use crate::enemies::formations::Formation;
+use crate::enemies::spawn_enemy;
+use crate::powerups::spawn_powerup;
+use crate::SimulationSystems;
+
+/// Authored waves, played in order from wave 1. Once they run out
+/// `procedural_wave` takes over, carrying on from the next wave
+/// number. Empty by default, so every wave is procedural.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct WaveTable {
+    pub waves: Vec<AuthoredWave>,
+}
+
+/// One wave as written in the wave file.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AuthoredWave {
+    /// Spawned in order, each `delay` seconds after the one before.
+    pub groups: Vec<SpawnGroup>,
+    /// Seconds of rest once this wave is cleared, before the next one.
+    #[serde(default = "breather")]
+    pub breather: f32,
+}
+
+fn breather() -> f32 {
+    WAVE_BREATHER
+}
+
+/// A batch of enemies of one archetype that arrive together.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SpawnGroup {
+    /// Name of an archetype in the `EnemyRegistry`.
+    pub archetype: String,
+    pub count: u32,
+    #[serde(default)]
+    pub color: GroupColor,
+    #[serde(default)]
+    pub formation: Formation,
+    /// Seconds after the previous group (or the start of the wave).
+    #[serde(default)]
+    pub delay: f32,
+}
+
+/// What color each enemy of a group is.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum GroupColor {
+    /// A coin flip from the room's RNG for each enemy.
+    #[default]
+    Random,
+    Red,
+    Blue,
+    /// Red, Blue, Red, ...
+    Alternating,
+}
+
+impl GroupColor {
+    fn pick(self, index: u32, rng: &mut GameRng) -> TeamColor {
+        match self {
+            GroupColor::Random if rng.random_bool(0.5) => TeamColor::Red,
+            GroupColor::Random => TeamColor::Blue,
+            GroupColor::Red => TeamColor::Red,
+            GroupColor::Blue => TeamColor::Blue,
+            GroupColor::Alternating if index.is_multiple_of(2) => TeamColor::Red,
+            GroupColor::Alternating => TeamColor::Blue,
+        }
+    }
+}
+
+impl WaveTable {
+    /// Read the wave table from a RON file. A missing or unreadable file
+    /// leaves every wave procedural, which is logged.
+    pub fn load(path: &Path, registry: &EnemyRegistry) -> Self {
+        let table = std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|ron| ron::from_str::<Self>(&ron).map_err(|err| err.to_string()));
+        match table {
+            Ok(table) => {
+                table.check(registry);
+                info!(
+                    "Loaded {} authored waves from {}",
+                    table.waves.len(),
+                    path.display()
+                );
+                table
+            }
+            Err(err) => {
+                warn!(
+                    "Using procedural waves only, could not load {}: {}",
+                    path.display(),
+                    err
+                );
+                Self::default()
+            }
+        }
+    }
+
+    /// Warn about groups naming archetypes the registry doesn't have, and
+    /// about clusters with a negative spread, which spawn as if it were
+    /// zero.
+    fn check(&self, registry: &EnemyRegistry) {
+        for (index, wave) in self.waves.iter().enumerate() {
+            for group in &wave.groups {
+                if registry.get(&group.archetype).is_none() {
+                    warn!(
+                        "Wave {} spawns unknown enemy archetype {}",
+                        index + 1,
+                        group.archetype
+                    );
+                }
+                if let Formation::Cluster { spread, .. } = group.formation {
+                    if spread < 0.0 || spread.is_nan() {
+                        warn!(
+                            "Wave {} spawns {} in a cluster with spread {}",
+                            index + 1,
+                            group.archetype,
+                            spread
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// The authored wave `wave`, if there is one.
+    pub fn wave(&self, wave: u32) -> Option<&AuthoredWave> {
+        self.waves.get((wave as usize).checked_sub(1)?)
+    }
+
+    /// Seconds of rest after clearing wave `wave`.
+    pub fn breather(&self, wave: u32) -> f32 {
+        self.wave(wave).map_or(WAVE_BREATHER, |wave| wave.breather)
+    }
+}
+
+/// Groups of the current wave still waiting for their delay, kept on the
+/// match entity. Authoritative only; never replicated.
+#[derive(Component, Clone, Debug, Default)]
+pub struct PendingGroups {
+    pub groups: VecDeque<SpawnGroup>,
+    /// Seconds until the front group spawns.
+    pub timer: f32,
+    /// Speed multiplier the wave started with.
+    pub speed_scale: f32,
+}
+
+impl PendingGroups {
+    fn new(groups: Vec<SpawnGroup>, speed_scale: f32) -> Self {
+        Self {
+            timer: groups.first().map_or(0.0, |group| group.delay),
+            groups: groups.into(),
+            speed_scale,
+        }
+    }
+
+    /// Spawn every group whose delay has run out after `dt` more seconds,
+    /// clear of the living `ships`. Returns how many enemies that spawned.
+    #[allow(clippy::too_many_arguments)]
+    fn release(
+        &mut self,
+        dt: f32,
+        commands: &mut Commands,
+        room: Entity,
+        ships: &[Vec2],
+        registry: &EnemyRegistry,
+        bounds: &ArenaBounds,
+        rng: &mut GameRng,
+    ) -> u32 {
+        self.timer -= dt;
+        let mut spawned = 0;
+        while self.timer <= 0.0 {
+            let Some(group) = self.groups.pop_front() else {
+                break;
+            };
+            spawned += spawn_group(
+                commands,
+                room,
+                &group,
+                self.speed_scale,
+                ships,
+                registry,
+                bounds,
+                rng,
+            )
+            .len() as u32;
+            self.timer += self.groups.front().map_or(0.0, |next| next.delay);
+        }
+        spawned
+    }
+}
+
+/// Spawn one group into `room`, its enemies `speed_scale` times as fast as
+/// their archetype, placed clear of the living `ships` and telegraphed for
+/// `SPAWN_TELEGRAPH` seconds before they arrive. Returns the enemies.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_group(
+    commands: &mut Commands,
+    room: Entity,
+    group: &SpawnGroup,
+    speed_scale: f32,
+    ships: &[Vec2],
+    registry: &EnemyRegistry,
+    bounds: &ArenaBounds,
+    rng: &mut GameRng,
+) -> Vec<Entity> {
+    let Some(archetype) = registry.get(&group.archetype) else {
+        warn!("Skipping group of unknown archetype {}", group.archetype);
+        return Vec::new();
+    };
+    let slots = group.formation.layout(group.count, rng, bounds, ships);
+    let mut enemies = Vec::with_capacity(slots.len());
+    for (index, slot) in (0..).zip(slots) {
+        let color = slot.color.unwrap_or_else(|| group.color.pick(index, rng));
+        let enemy = spawn_enemy(
+            commands,
+            room,
+            archetype,
+            color,
+            slot.pos,
+            slot.heading,
+            speed_scale,
+            bounds,
+            rng,
+        );
+        commands.entity(enemy).insert(SpawnWarning(SPAWN_TELEGRAPH));
+        enemies.push(enemy);
+    }
+    enemies
+}
+
+/// Roll procedural wave `wave` at `difficulty`: its size, then each enemy's
+/// color and archetype (by weight from the registry), all from the room's
+/// RNG. Its enemies come from the walls `SPAWN_BATCH_SIZE` at a time,
+/// `SPAWN_BATCH_INTERVAL` seconds apart.
+pub fn procedural_wave(
+    wave: u32,
+    difficulty: Difficulty,
+    registry: &EnemyRegistry,
+    rng: &mut GameRng,
+) -> Vec<SpawnGroup> {
+    let count = (((wave * 3 + 2) as f32 * difficulty.enemy_count).round() as usize).max(1);
+
+    let mut groups = Vec::with_capacity(count);
+    for index in 0..count {
+        let color = if rng.random_bool(0.5) {
+            GroupColor::Red
+        } else {
+            GroupColor::Blue
+        };
+        let roll: f32 = rng.random();
+        let Some(archetype) = registry.pick(wave, roll, difficulty.chaser_share) else {
+            warn!("No enemy archetype can spawn in wave {}", wave);
+            return Vec::new();
+        };
+        let new_batch = index > 0 && index.is_multiple_of(SPAWN_BATCH_SIZE);
+        groups.push(SpawnGroup {
+            archetype: archetype.name.clone(),
+            count: 1,
+            color,
+            formation: Formation::Edges,
+            delay: if new_batch { SPAWN_BATCH_INTERVAL } else { 0.0 },
+        });
+    }
+    groups
+}
+
+/// Runs every playing room's waves, with the difficulty director adapting
+/// them to the team. Authoritative only: the server, or a local sim that
+/// asks for waves.
+pub struct WavesPlugin {
+    pub director: DirectorConfig,
+}
+
+impl Plugin for WavesPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Director::new(self.director));
+        app.add_observer(director::track_deaths);
+        app.add_observer(director::track_kills);
+        app.add_systems(FixedUpdate, wave_manager.after(SimulationSystems));
+    }
+}
+
+/// The boss fight procedural wave `wave` is, every `BOSS_WAVE_INTERVAL`
+/// waves if the registry has a boss: the boss alone, in the middle of the
+/// arena.
+pub fn boss_wave(wave: u32, registry: &EnemyRegistry) -> Option<Vec<SpawnGroup>> {
+    if !wave.is_multiple_of(BOSS_WAVE_INTERVAL) {
+        return None;
+    }
+    let boss = registry.boss()?;
+    Some(vec![SpawnGroup {
+        archetype: boss.name.clone(),
+        count: 1,
+        color: GroupColor::Random,
+        formation: Formation::Cluster {
+            at: Vec2::ZERO,
+            spread: 0.0,
+        },
+        delay: 0.0,
+    }])
+}
+
+/// Run the waves of every room that is playing: authored waves from the
+/// `WaveTable` while it lasts, procedural ones and boss fights after, each
+/// group arriving as its delay runs out. A wave is cleared once its last
+/// group has spawned and every enemy is gone, and the director reviews it.
+/// Authored waves keep their counts and archetypes; the director's speed
+/// applies to every wave.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn wave_manager(
+    mut rooms: Query<(
+        Entity,
+        Option<&RoomName>,
+        &MatchState,
+        &mut WaveState,
+        &mut GameRng,
+        Option<&mut PendingGroups>,
+    )>,
+    enemies: Query<&InRoom, With<EnemyType>>,
+    ships: Query<(&Position, &Health, &InRoom), (With<Ship>, Without<Parked>)>,
+    table: Res<WaveTable>,
+    registry: Res<EnemyRegistry>,
+    mut director: ResMut<Director>,
+    bounds: Res<ArenaBounds>,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+
+    for (room, name, state, mut wave, mut rng, pending) in rooms.iter_mut() {
+        if *state != MatchState::Playing {
+            continue;
+        }
+        let name = name.map_or("local", |name| name.0.as_str());
+        let living: Vec<Vec2> = ships
+            .iter()
+            .filter(|(_, health, in_room)| health.alive && in_room.0 == room)
+            .map(|(pos, ..)| pos.0)
+            .collect();
+
+        // Count remaining enemies, plus any groups arriving this tick
+        wave.enemies_remaining = enemies.iter().filter(|in_room| in_room.0 == room).count() as u32;
+        let mut waiting = false;
+        if let Some(mut pending) = pending {
+            let spawned = pending.release(
+                dt,
+                &mut commands,
+                room,
+                &living,
+                &registry,
+                &bounds,
+                &mut rng,
+            );
+            director.spawned(room, spawned);
+            wave.enemies_remaining += spawned;
+            waiting = !pending.groups.is_empty();
+        }
+
+        if wave.active {
+            director.tick(room, dt);
+        }
+
+        if wave.active && wave.enemies_remaining == 0 && !waiting {
+            // Wave cleared, start breather
+            info!(
+                "Room {}: wave {} cleared, starting breather",
+                name, wave.current_wave
+            );
+            director.wave_cleared(room, name);
+            wave.active = false;
+            wave.breather_timer = table.breather(wave.current_wave);
+        }
+
+        if !wave.active {
+            wave.breather_timer -= dt;
+            if wave.breather_timer <= 0.0 {
+                wave.current_wave += 1;
+                director.wave_started(room, wave.current_wave);
+                let difficulty = director.difficulty(room);
+                let groups = match table.wave(wave.current_wave) {
+                    Some(authored) => {
+                        info!(
+                            "Room {}: starting authored wave {}",
+                            name, wave.current_wave
+                        );
+                        authored.groups.clone()
+                    }
+                    None => match boss_wave(wave.current_wave, &registry) {
+                        Some(groups) => {
+                            info!("Room {}: starting boss wave {}", name, wave.current_wave);
+                            groups
+                        }
+                        None => {
+                            info!("Room {}: starting wave {}", name, wave.current_wave);
+                            procedural_wave(wave.current_wave, difficulty, &registry, &mut rng)
+                        }
+                    },
+                };
+                let mut pending = PendingGroups::new(groups, difficulty.speed);
+                let spawned = pending.release(
+                    0.0,
+                    &mut commands,
+                    room,
+                    &living,
+                    &registry,
+                    &bounds,
+                    &mut rng,
+                );
+                commands.entity(room).insert(pending);
+                director.spawned(room, spawned);
+                wave.enemies_remaining = spawned;
+                if wave.current_wave.is_multiple_of(ROPE_WAVE_INTERVAL) {
+                    spawn_powerup(
+                        &mut commands,
+                        room,
+                        PowerUp::ElectricRope,
+                        &bounds,
+                        &mut rng,
+                    );
+                }
+                wave.active = true;
+            }
+        }
+    }
+}
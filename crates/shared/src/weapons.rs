@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use lightyear::prelude::input::native::ActionState;
+use lightyear::prelude::{PreSpawned, PredictionDespawnCommandsExt};
+
+use crate::archetypes::EnemyRegistry;
+use crate::components::*;
+use crate::enemies::SplitterDestroyed;
+use crate::protocol::PlayerInput;
+use crate::scoring::EnemyKilled;
+use crate::Rooms;
+
+/// Living ships fire along their aim whenever their weapon has cooled down.
+/// The server fires for every ship; a client fires for the ships it has
+/// inputs for, so its own shots appear at once instead of a round trip
+/// late. See [`pre_spawn_shot`] for how the two copies are matched.
+#[allow(clippy::type_complexity)]
+pub fn fire_system(
+    mut ships: Query<
+        (
+            Entity,
+            &mut Weapon,
+            &Position,
+            &TeamColor,
+            &Health,
+            &ActionState<PlayerInput>,
+            Option<&InRoom>,
+        ),
+        (With<Ship>, Without<Parked>),
+    >,
+    rooms: Rooms,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+
+    for (ship, mut weapon, pos, color, health, input, room) in ships.iter_mut() {
+        if !rooms.playing(room) {
+            continue;
+        }
+
+        weapon.cooldown = (weapon.cooldown - dt).max(0.0);
+        let Some(aim) = input.0.aim.try_normalize() else {
+            continue;
+        };
+        if !health.alive || weapon.cooldown > 0.0 {
+            continue;
+        }
+
+        weapon.cooldown = FIRE_COOLDOWN;
+        let shot = commands
+            .spawn((
+                *color,
+                Projectile {
+                    remaining: PROJECTILE_LIFETIME,
+                },
+                FiredBy(ship),
+                Position(pos.0 + aim * SHIP_RADIUS),
+                Velocity(aim * PROJECTILE_SPEED),
+                Radius(PROJECTILE_RADIUS),
+            ))
+            .id();
+        if let Some(room) = room {
+            commands.entity(shot).insert(*room);
+        }
+    }
+}
+
+/// Mark a freshly fired shot as pre-spawned, on the server and on the
+/// client that fired it alike. Both hash the shot from its tick and
+/// replicated components, salted with its color so the Red and Blue ships
+/// firing on the same tick don't collide; the client then adopts the
+/// server's shot as the one it already shows.
+pub fn pre_spawn_shot(trigger: On<Add, FiredBy>, shots: Query<&TeamColor>, mut commands: Commands) {
+    if let Ok(color) = shots.get(trigger.entity) {
+        commands
+            .entity(trigger.entity)
+            .insert(PreSpawned::default_with_salt(*color as u64));
+    }
+}
+
+/// Fly shots, drop those that run out of time or leave the arena, and
+/// resolve their hits. A shot destroys the first enemy it touches if their
+/// colors match, crediting the ship that fired it, or takes a hit point off
+/// a tough one; otherwise the enemy, or a shielder's shield, soaks it up.
+#[allow(clippy::type_complexity)]
+pub fn projectile_system(
+    mut projectiles: Query<(
+        Entity,
+        &mut Projectile,
+        &mut Position,
+        &Velocity,
+        &TeamColor,
+        &Radius,
+        Option<&FiredBy>,
+        Option<&InRoom>,
+    )>,
+    mut enemies: Query<
+        (
+            Entity,
+            &EnemyType,
+            &Archetype,
+            &Position,
+            &TeamColor,
+            &Radius,
+            Option<&Facing>,
+            Option<&mut EnemyHealth>,
+            Option<&SpeedScale>,
+            Option<&InRoom>,
+        ),
+        (Without<Ship>, Without<Projectile>, Without<SpawnWarning>),
+    >,
+    rooms: Rooms,
+    registry: Res<EnemyRegistry>,
+    bounds: Res<ArenaBounds>,
+    time: Res<Time<Fixed>>,
+    mut commands: Commands,
+) {
+    let dt = time.delta_secs();
+    let mut destroyed = Vec::new();
+
+    for (shot, mut projectile, mut shot_pos, vel, shot_color, shot_radius, fired_by, shot_room) in
+        projectiles.iter_mut()
+    {
+        if !rooms.playing(shot_room) {
+            continue;
+        }
+
+        projectile.remaining -= dt;
+        shot_pos.0 += vel.0 * dt;
+        if projectile.remaining <= 0.0
+            || shot_pos.0.x.abs() > bounds.half_width
+            || shot_pos.0.y.abs() > bounds.half_height
+        {
+            commands.entity(shot).prediction_despawn();
+            continue;
+        }
+
+        let hit = enemies.iter_mut().find(
+            |(enemy_entity, _, _, enemy_pos, _, enemy_radius, _, _, _, enemy_room)| {
+                !destroyed.contains(enemy_entity)
+                    && *enemy_room == shot_room
+                    && shot_pos.0.distance(enemy_pos.0) < shot_radius.0 + enemy_radius.0
+            },
+        );
+        let Some((
+            enemy_entity,
+            enemy_type,
+            archetype,
+            enemy_pos,
+            enemy_color,
+            _,
+            facing,
+            enemy_health,
+            speed_scale,
+            enemy_room,
+        )) = hit
+        else {
+            continue;
+        };
+
+        commands.entity(shot).prediction_despawn();
+        let shielded =
+            facing.is_some_and(|facing| facing.direction().dot(shot_pos.0 - enemy_pos.0) > 0.0);
+        if shielded || enemy_color != shot_color {
+            continue;
+        }
+        if enemy_health.is_some_and(|mut health| !health.hit()) {
+            continue;
+        }
+
+        commands.entity(enemy_entity).prediction_despawn();
+        destroyed.push(enemy_entity);
+        if let Some(fired_by) = fired_by {
+            commands.trigger(EnemyKilled {
+                ship: fired_by.0,
+                enemy: *enemy_type,
+                points: registry.of(archetype).map_or(0, |stats| stats.points),
+            });
+        }
+        if *enemy_type == EnemyType::Splitter {
+            commands.trigger(SplitterDestroyed {
+                position: enemy_pos.0,
+                color: *enemy_color,
+                away: vel.0,
+                room: enemy_room.copied(),
+                splitter: archetype.clone(),
+                speed_scale: speed_scale.map_or(1.0, |scale| scale.0),
+            });
+        }
+    }
+}
@@ -1 +1,2 @@
+mod net;
 pub mod sim;
@@ -0,0 +1,378 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use lightyear::connection::host::HostClient;
+use lightyear::crossbeam::CrossbeamIo;
+use lightyear::link::LinkStart;
+use lightyear::prelude::client::input::InputSystems;
+use lightyear::prelude::client::*;
+use lightyear::prelude::input::native::{ActionState, InputMarker};
+use lightyear::prelude::server::ServerPlugins;
+use lightyear::prelude::*;
+use schizoid_server::game::{ServerGamePlugin, ServerTransport};
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::components::{GameRng, Health, Ship, TeamColor};
+use schizoid_shared::protocol::{
+    ChooseColor, JoinRoom, LeaderboardMessage, MatchResult, PlayerInput, Rejected, ReliableChannel,
+    RequestRematch, SetReady,
+};
+use schizoid_shared::{SharedPlugin, SimulationPlugin, TICK_DURATION};
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+use crate::sim::NetworkCondition;
+
+/// One in-process client: its own App, plus the entity representing it on the server.
+pub(crate) struct SimClient {
+    pub(crate) app: App,
+    /// The client's `Client` entity in its own world.
+    pub(crate) client: Entity,
+    /// The `LinkOf`/`ClientOf` entity for this client in the server world.
+    pub(crate) link: Entity,
+    /// Netcode client id; reconnecting with it gets a parked ship back.
+    pub(crate) id: u64,
+    pub(crate) condition: NetworkCondition,
+}
+
+/// Input the test wants each of this client's ships (by color) to send.
+/// Written into the ActionState the same way `client::input::buffer_input`
+/// does.
+#[derive(Resource, Default)]
+struct SimInput(HashMap<TeamColor, PlayerInput>);
+
+/// Latest leaderboard the server sent this client.
+#[derive(Resource, Default)]
+struct ReceivedLeaderboard(Option<Vec<MatchResult>>);
+
+/// Every request the server has turned down for this client, oldest first.
+#[derive(Resource, Default)]
+struct ReceivedRejections(Vec<Rejected>);
+
+/// Build the authoritative server App: the real `ServerGamePlugin` with no IO,
+/// ticking exactly once per `update()`.
+pub(crate) fn server_app(rules: MatchRules) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    add_server(&mut app, rules);
+    start(app)
+}
+
+/// Build a server App that is also its own client, the way
+/// `schizoid-client --local` runs: lightyear's host-client mode, with no link
+/// or IO in between. Returns the App and the host's `Client` entity, which
+/// connects on the next update.
+pub(crate) fn host_app(rules: MatchRules) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins {
+        tick_duration: TICK_DURATION,
+    });
+    add_server(&mut app, rules);
+    app.init_resource::<SimInput>();
+    app.add_systems(
+        FixedPreUpdate,
+        write_input.in_set(InputSystems::WriteClientInputs),
+    );
+    app.add_observer(mark_host_ships);
+    let mut app = start(app);
+
+    let server = app
+        .world_mut()
+        .query_filtered::<Entity, With<Server>>()
+        .single(app.world())
+        .expect("server app has no Server entity");
+    let client = app
+        .world_mut()
+        .spawn((Client::default(), LinkOf { server }))
+        .id();
+    app.world_mut().trigger(Connect { entity: client });
+    (app, client)
+}
+
+fn add_server(app: &mut App, rules: MatchRules) {
+    app.add_plugins(ServerPlugins {
+        tick_duration: TICK_DURATION,
+    });
+    app.add_plugins(SharedPlugin);
+    app.add_plugins(ServerGamePlugin {
+        transport: ServerTransport::Local,
+        spawn_waves: false,
+        seed: None,
+        leaderboard: None,
+        rules,
+    });
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK_DURATION));
+}
+
+fn start(mut app: App) -> App {
+    app.finish();
+    app.cleanup();
+    // Run Startup so the server entity exists and is started
+    app.update();
+    app
+}
+
+/// Input for the host's ship of `color` in a host App, as
+/// `SimClient::input` is for a linked client.
+pub(crate) fn host_input(app: &mut App, color: TeamColor) -> Mut<'_, PlayerInput> {
+    app.world_mut()
+        .resource_mut::<SimInput>()
+        .map_unchanged(|input| input.0.entry(color).or_default())
+}
+
+/// Send a message from the host client, which the server in the same App
+/// receives on that very entity.
+pub(crate) fn send_from_host<M: lightyear::prelude::Message>(
+    app: &mut App,
+    host: Entity,
+    message: M,
+) {
+    app.world_mut()
+        .get_mut::<MessageSender<M>>(host)
+        .expect("host cannot send this message")
+        .send::<ReliableChannel>(message);
+}
+
+impl NetworkCondition {
+    fn link(self) -> Link {
+        let config = match self {
+            NetworkCondition::Ideal => return Link::new(None),
+            NetworkCondition::Good => LinkConditionerConfig::good_condition(),
+            NetworkCondition::Average => LinkConditionerConfig::average_condition(),
+            NetworkCondition::Poor => LinkConditionerConfig::poor_condition(),
+        };
+        Link::new(Some(RecvLinkConditioner::new(config)))
+    }
+}
+
+/// Build a client App and link it to the server over a crossbeam channel pair.
+/// `condition` is applied to incoming packets on both ends of the link.
+pub(crate) fn connect_client(
+    server_app: &mut App,
+    client_id: u64,
+    condition: NetworkCondition,
+) -> SimClient {
+    let server = server_app
+        .world_mut()
+        .query_filtered::<Entity, With<Server>>()
+        .single(server_app.world())
+        .expect("server app has no Server entity");
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins {
+        tick_duration: TICK_DURATION,
+    });
+    app.add_plugins(SharedPlugin);
+    app.add_plugins(SimulationPlugin);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(TICK_DURATION));
+    app.init_resource::<SimInput>();
+    app.init_resource::<ReceivedLeaderboard>();
+    app.init_resource::<ReceivedRejections>();
+    app.add_systems(Update, (receive_leaderboard, receive_rejections));
+    app.add_systems(
+        FixedPreUpdate,
+        write_input.in_set(InputSystems::WriteClientInputs),
+    );
+    app.add_observer(mark_own_ship);
+    app.finish();
+    app.cleanup();
+
+    let (client_io, server_io) = CrossbeamIo::new_pair();
+
+    let link = server_app
+        .world_mut()
+        .spawn((LinkOf { server }, condition.link(), server_io))
+        .id();
+    server_app.world_mut().trigger(LinkStart { entity: link });
+
+    let auth = Authentication::Manual {
+        server_addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 0),
+        client_id,
+        private_key: [0u8; 32],
+        protocol_id: 0,
+    };
+    let client = app
+        .world_mut()
+        .spawn((
+            Client::default(),
+            ReplicationReceiver::default(),
+            PredictionManager::default(),
+            NetcodeClient::new(auth, NetcodeConfig::default()).unwrap(),
+            condition.link(),
+            client_io,
+        ))
+        .id();
+    app.world_mut().trigger(Connect { entity: client });
+
+    SimClient {
+        app,
+        client,
+        link,
+        id: client_id,
+        condition,
+    }
+}
+
+impl SimClient {
+    /// Input this client sends for its ship of `color`.
+    pub(crate) fn input(&mut self, color: TeamColor) -> Mut<'_, PlayerInput> {
+        self.app
+            .world_mut()
+            .resource_mut::<SimInput>()
+            .map_unchanged(|input| input.0.entry(color).or_default())
+    }
+
+    pub(crate) fn disconnect(&mut self) {
+        self.app.world_mut().trigger(Disconnect {
+            entity: self.client,
+        });
+    }
+
+    pub(crate) fn is_connected(&self) -> bool {
+        self.app.world().get::<Connected>(self.client).is_some()
+    }
+
+    /// The client's predicted copy of its own ship, once replicated.
+    pub(crate) fn own_ship(&mut self) -> Option<Entity> {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, (With<Ship>, With<InputMarker<PlayerInput>>)>()
+            .iter(self.app.world())
+            .next()
+    }
+
+    /// How many ships this client steers: two when flying solo.
+    pub(crate) fn own_ship_count(&mut self) -> usize {
+        self.app
+            .world_mut()
+            .query_filtered::<(), (With<Ship>, With<InputMarker<PlayerInput>>)>()
+            .iter(self.app.world())
+            .count()
+    }
+
+    /// Whether the client currently predicts its own ship as alive.
+    pub(crate) fn own_ship_alive(&mut self) -> bool {
+        let ship = self.own_ship().expect("client has no predicted ship");
+        self.app
+            .world()
+            .get::<Health>(ship)
+            .expect("predicted ship has no Health")
+            .alive
+    }
+
+    pub(crate) fn leaderboard(&self) -> Option<Vec<MatchResult>> {
+        self.app.world().resource::<ReceivedLeaderboard>().0.clone()
+    }
+
+    pub(crate) fn request_rematch(&mut self) {
+        self.send(RequestRematch);
+    }
+
+    pub(crate) fn join_room(&mut self, room: &str, color: Option<TeamColor>, both_ships: bool) {
+        self.send(JoinRoom {
+            room: room.to_string(),
+            color,
+            both_ships,
+        });
+    }
+
+    pub(crate) fn rejections(&self) -> Vec<Rejected> {
+        self.app.world().resource::<ReceivedRejections>().0.clone()
+    }
+
+    pub(crate) fn choose_color(&mut self, color: TeamColor) {
+        self.send(ChooseColor(color));
+    }
+
+    pub(crate) fn set_ready(&mut self, ready: bool) {
+        self.send(SetReady(ready));
+    }
+
+    fn send<M: lightyear::prelude::Message>(&mut self, message: M) {
+        self.app
+            .world_mut()
+            .get_mut::<MessageSender<M>>(self.client)
+            .expect("client cannot send this message")
+            .send::<ReliableChannel>(message);
+    }
+
+    /// Seed of the match RNG for the room this client sees.
+    pub(crate) fn rng_seed(&mut self) -> u64 {
+        self.app
+            .world_mut()
+            .query::<&GameRng>()
+            .single(self.app.world())
+            .expect("client sees no match")
+            .seed()
+    }
+
+    /// Map a server entity to the client's local copy of it.
+    pub(crate) fn local_entity(&self, server_entity: Entity) -> Option<Entity> {
+        self.app
+            .world()
+            .get::<MessageManager>(self.client)?
+            .entity_mapper
+            .get_local(server_entity)
+    }
+}
+
+fn write_input(
+    mut query: Query<(&mut ActionState<PlayerInput>, &TeamColor), With<InputMarker<PlayerInput>>>,
+    input: Res<SimInput>,
+) {
+    for (mut action_state, color) in query.iter_mut() {
+        action_state.0 = input.0.get(color).cloned().unwrap_or_default();
+    }
+}
+
+fn receive_leaderboard(
+    mut receivers: Query<&mut MessageReceiver<LeaderboardMessage>>,
+    mut received: ResMut<ReceivedLeaderboard>,
+) {
+    for mut receiver in receivers.iter_mut() {
+        if let Some(message) = receiver.receive().last() {
+            received.0 = Some(message.entries);
+        }
+    }
+}
+
+fn receive_rejections(
+    mut receivers: Query<&mut MessageReceiver<Rejected>>,
+    mut received: ResMut<ReceivedRejections>,
+) {
+    for mut receiver in receivers.iter_mut() {
+        received.0.extend(receiver.receive());
+    }
+}
+
+/// Same as the real client in `--local` mode: the server's ships handed to
+/// the host take input directly, with nothing predicted.
+fn mark_host_ships(
+    trigger: On<Add, ControlledBy>,
+    ships: Query<&ControlledBy, With<Ship>>,
+    hosts: Query<(), With<HostClient>>,
+    mut commands: Commands,
+) {
+    let Ok(controlled) = ships.get(trigger.entity) else {
+        return;
+    };
+    if hosts.contains(controlled.owner) {
+        commands
+            .entity(trigger.entity)
+            .insert(InputMarker::<PlayerInput>::default());
+    }
+}
+
+/// Same as the real client: the predicted ship we control takes input.
+#[allow(clippy::type_complexity)]
+fn mark_own_ship(
+    trigger: On<Add, (Ship, Predicted, Controlled)>,
+    ships: Query<(), (With<Ship>, With<Predicted>, With<Controlled>)>,
+    mut commands: Commands,
+) {
+    if ships.get(trigger.entity).is_ok() {
+        commands
+            .entity(trigger.entity)
+            .insert(InputMarker::<PlayerInput>::default());
+    }
+}
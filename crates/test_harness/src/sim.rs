@@ -1,24 +1,77 @@
 use bevy::prelude::*;
-use lightyear::prelude::input::native::ActionState;
+use bevy::time::TimeUpdateStrategy;
+use lightyear::prelude::input::native::{ActionState, InputMarker};
+use lightyear::prelude::{Connected, ControlledBy, Interpolated};
+use schizoid_server::leaderboard::Leaderboard;
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::archetypes::EnemyRegistry;
 use schizoid_shared::components::*;
-use schizoid_shared::protocol::PlayerInput;
-use schizoid_shared::systems;
+use schizoid_shared::director::{Difficulty, Director, DirectorConfig, DirectorDecision};
+use schizoid_shared::enemies;
+use schizoid_shared::enemies::formations::Formation;
+use schizoid_shared::protocol::{JoinRoom, MatchResult, PlayerInput, Rejected, SetReady};
+use schizoid_shared::waves::{self, GroupColor, SpawnGroup, WaveTable, WavesPlugin};
+use schizoid_shared::{AuthorityPlugin, SimulationPlugin, TICK_DURATION};
+use std::time::Duration;
+
+use crate::net::{self, SimClient};
+
+/// Ticks to wait for networked clients to connect and receive their ship.
+const CONNECT_TIMEOUT_TICKS: u32 = 600;
+
+/// Room the networked constructors seat every client in.
+const TEST_ROOM: &str = "test";
+
+/// Link conditions applied to every client link in networked mode.
+/// Latency and jitter apply to incoming packets on each end, so the round
+/// trip is roughly twice the listed latency.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NetworkCondition {
+    /// No conditioning: packets arrive on the next frame.
+    #[default]
+    Ideal,
+    /// 40ms latency, 6ms jitter, 0.2% loss.
+    Good,
+    /// 100ms latency, 15ms jitter, 2% loss.
+    Average,
+    /// 200ms latency, 30ms jitter, 10% loss.
+    Poor,
+}
 
 /// Test simulation harness for game logic.
 /// Wraps a Bevy App with MinimalPlugins for headless testing.
+///
+/// In networked mode `app` is the authoritative server and every accessor
+/// reads server state; clients are separate Apps linked over crossbeam.
+/// In hosted mode `app` is the server and its own client at once.
 pub struct GameSim {
     app: App,
+    clients: Vec<SimClient>,
+    /// The host's `Client` entity in hosted mode.
+    host: Option<Entity>,
+    /// Room the room-scoped helpers use. When `None`, the server's only room.
+    room: Option<Entity>,
 }
 
 impl GameSim {
     /// Create a new local simulation (no networking).
     pub fn new() -> Self {
+        Self::with_tick_duration(TICK_DURATION)
+    }
+
+    /// Create a local simulation ticking at `hz` instead of the game's default rate.
+    pub fn with_tick_rate(hz: f64) -> Self {
+        Self::with_tick_duration(Duration::from_secs_f64(1.0 / hz))
+    }
+
+    fn with_tick_duration(tick: Duration) -> Self {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
 
         // Initialize resources without networking plugins
-        app.init_resource::<WaveState>();
         app.init_resource::<ArenaBounds>();
+        app.init_resource::<EnemyRegistry>();
+        app.init_resource::<WaveTable>();
 
         // Register component types for reflection
         app.register_type::<TeamColor>();
@@ -27,50 +80,254 @@ impl GameSim {
         app.register_type::<Radius>();
         app.register_type::<Health>();
         app.register_type::<EnemyType>();
+        app.register_type::<Archetype>();
         app.register_type::<Velocity>();
         app.register_type::<OrbitData>();
+        app.register_type::<Facing>();
+        app.register_type::<Score>();
+        app.register_type::<Weapon>();
+        app.register_type::<Projectile>();
+        app.register_type::<MatchState>();
+        app.register_type::<ShipHandling>();
+
+        // Same FixedUpdate chain as the server; each update() advances exactly one tick
+        app.add_plugins(SimulationPlugin);
+        app.add_plugins(AuthorityPlugin);
+        // No lobby locally: one room whose match is always on
+        let room = app
+            .world_mut()
+            .spawn((
+                MatchState::Playing,
+                WaveState::default(),
+                GameRng::default(),
+            ))
+            .id();
+        app.insert_resource(Time::<Fixed>::from_duration(tick));
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(tick));
+        // The first update only starts the clock; no fixed tick runs yet
+        app.update();
+
+        Self {
+            app,
+            clients: Vec::new(),
+            host: None,
+            room: Some(room),
+        }
+    }
 
-        // Register systems in Update schedule (MinimalPlugins doesn't include FixedUpdate)
-        app.add_systems(
-            Update,
-            (
-                systems::ship_movement,
-                systems::enemy_movement,
-                systems::chaser_ai,
-                systems::collision_system,
-                systems::respawn_system,
-            ),
+    /// Create a local simulation whose match RNG starts from `seed`.
+    pub fn with_seed(seed: u64) -> Self {
+        let mut sim = Self::new();
+        let room = sim.room();
+        sim.app
+            .world_mut()
+            .entity_mut(room)
+            .insert(GameRng::new(seed));
+        sim
+    }
+
+    /// Create a client-server simulation: the real `ServerGamePlugin` plus
+    /// `players` client Apps, linked in-process over crossbeam channels.
+    /// Every client joins one room (in order, so client 0 flies Red) and
+    /// readies up. Returns once each is predicting its own ship and the
+    /// match is `Playing`.
+    ///
+    /// Waves are disabled; place enemies with the `spawn_*` helpers.
+    /// Steps run in real time so lightyear's clocks stay in sync.
+    pub fn networked(players: usize) -> Self {
+        Self::networked_with(players, NetworkCondition::Ideal)
+    }
+
+    /// Like [`GameSim::networked`], with `condition` on every client link.
+    pub fn networked_with(players: usize, condition: NetworkCondition) -> Self {
+        // Start playing as soon as anyone connects
+        let rules = MatchRules {
+            players: 1,
+            countdown: 0.0,
+            ..default()
+        };
+        let rooms = vec![TEST_ROOM; players];
+        let mut sim = Self::connect(&rooms, condition, rules);
+        sim.wait_for(|sim| sim.match_state() == MatchState::Playing);
+        sim
+    }
+
+    /// Like [`GameSim::networked`], with the server using `rules`. Returns
+    /// once every client is seated and ready, whatever state the match is in.
+    pub fn networked_with_rules(players: usize, rules: MatchRules) -> Self {
+        let rooms = vec![TEST_ROOM; players];
+        Self::connect(&rooms, NetworkCondition::Ideal, rules)
+    }
+
+    /// Like [`GameSim::networked`], but client `i` joins `rooms[i]`, so one
+    /// server hosts several matches. Returns once every room is `Playing`.
+    /// Room-scoped helpers need a [`GameSim::select_room`] first.
+    pub fn networked_in_rooms(rooms: &[&str]) -> Self {
+        let rules = MatchRules {
+            players: 1,
+            countdown: 0.0,
+            ..default()
+        };
+        let mut sim = Self::connect(rooms, NetworkCondition::Ideal, rules);
+        sim.wait_for(|sim| {
+            sim.app
+                .world_mut()
+                .query::<&MatchState>()
+                .iter(sim.app.world())
+                .all(|state| *state == MatchState::Playing)
+        });
+        sim
+    }
+
+    /// Like [`GameSim::networked`], with a single client flying both ships
+    /// (solo "Uber" mode).
+    pub fn networked_solo() -> Self {
+        let rules = MatchRules {
+            players: 1,
+            countdown: 0.0,
+            ..default()
+        };
+        let mut sim = Self::link(1, NetworkCondition::Ideal, rules);
+        sim.join_solo(0, TEST_ROOM);
+        sim.wait_for(|sim| sim.seated(0));
+        sim.set_ready(0, true);
+        sim.wait_for(|sim| {
+            sim.match_state() == MatchState::Playing && sim.clients[0].own_ship_count() == 2
+        });
+        sim
+    }
+
+    /// Create a simulation run the way `schizoid-client --local` plays: the
+    /// real `ServerGamePlugin` in one App that is also its own client, with
+    /// no network at all. The host joins a room (flying both ships when
+    /// `solo`) and readies up; returns once the match is `Playing` and the
+    /// host steers its ships.
+    pub fn hosted(solo: bool) -> Self {
+        let rules = MatchRules {
+            players: 1,
+            countdown: 0.0,
+            ..default()
+        };
+        let (app, host) = net::host_app(rules);
+        let mut sim = Self {
+            app,
+            clients: Vec::new(),
+            host: Some(host),
+            room: None,
+        };
+        sim.wait_for(|sim| sim.app.world().get::<Connected>(host).is_some());
+
+        net::send_from_host(
+            &mut sim.app,
+            host,
+            JoinRoom {
+                room: TEST_ROOM.to_string(),
+                color: None,
+                both_ships: solo,
+            },
         );
+        let ships = if solo { 2 } else { 1 };
+        sim.wait_for(|sim| sim.host_ships().len() == ships);
+        net::send_from_host(&mut sim.app, host, SetReady(true));
+        sim.wait_for(|sim| sim.match_state() == MatchState::Playing);
+        sim
+    }
+
+    /// Connect `players` clients that wait in the lobby without joining a
+    /// room; seat them with [`GameSim::join_room`].
+    pub fn networked_lobby(players: usize, rules: MatchRules) -> Self {
+        Self::link(players, NetworkCondition::Ideal, rules)
+    }
+
+    fn connect(rooms: &[&str], condition: NetworkCondition, rules: MatchRules) -> Self {
+        let mut sim = Self::link(rooms.len(), condition, rules);
+        for (index, room) in rooms.iter().enumerate() {
+            // One at a time, so colors go out in client order
+            sim.join_room(index, room);
+            sim.wait_for(|sim| sim.seated(index));
+            sim.set_ready(index, true);
+            sim.wait_for(|sim| sim.clients[index].own_ship().is_some());
+        }
+        sim
+    }
+
+    fn link(players: usize, condition: NetworkCondition, rules: MatchRules) -> Self {
+        assert!(players > 0, "a networked sim needs at least one client");
+
+        let mut app = net::server_app(rules);
+        let clients = (0..players)
+            .map(|id| net::connect_client(&mut app, id as u64, condition))
+            .collect();
+        let mut sim = Self {
+            app,
+            clients,
+            host: None,
+            room: None,
+        };
+
+        sim.wait_for(|sim| sim.clients.iter().all(|client| client.is_connected()));
+        sim
+    }
 
-        Self { app }
+    /// Step until `ready` holds, giving up after `CONNECT_TIMEOUT_TICKS`.
+    fn wait_for(&mut self, mut ready: impl FnMut(&mut Self) -> bool) {
+        for _ in 0..CONNECT_TIMEOUT_TICKS {
+            if ready(self) {
+                return;
+            }
+            self.step(1);
+        }
+        panic!("networked sim was not ready within {CONNECT_TIMEOUT_TICKS} ticks");
     }
 
     /// Spawn a player ship at a position.
+    ///
+    /// In networked mode ships belong to connected clients, so this moves the
+    /// server's ship of that color in the current room to `pos` instead of
+    /// spawning a new one.
     pub fn spawn_player(&mut self, color: TeamColor, pos: Vec2) -> Entity {
+        let room = self.in_room();
+        if self.is_networked() {
+            let ship = self
+                .app
+                .world_mut()
+                .query_filtered::<(Entity, &TeamColor, &InRoom), (With<Ship>, With<ControlledBy>)>()
+                .iter(self.app.world())
+                .find(|(_, ship_color, in_room)| **ship_color == color && **in_room == room)
+                .map(|(entity, ..)| entity)
+                .unwrap_or_else(|| panic!("no connected client controls a {color:?} ship"));
+            self.app.world_mut().get_mut::<Position>(ship).unwrap().0 = pos;
+            return ship;
+        }
+
         self.app
             .world_mut()
             .spawn((
                 Ship,
+                room,
                 color,
                 Position(pos),
                 Velocity(Vec2::ZERO),
                 Radius(SHIP_RADIUS),
                 Health::default(),
+                Score::default(),
+                Weapon::default(),
                 // For tests without networking, we need to provide ActionState manually
-                ActionState(PlayerInput {
-                    direction: Vec2::ZERO,
-                }),
+                ActionState(PlayerInput::default()),
             ))
             .id()
     }
 
     /// Spawn a drifter enemy.
     pub fn spawn_drifter(&mut self, color: TeamColor, pos: Vec2, vel: Vec2) -> Entity {
+        let room = self.in_room();
         self.app
             .world_mut()
             .spawn((
+                room,
                 color,
                 EnemyType::Drifter,
+                Archetype("drifter".to_string()),
                 Position(pos),
                 Velocity(vel),
                 Radius(ENEMY_RADIUS),
@@ -80,11 +337,14 @@ impl GameSim {
 
     /// Spawn a chaser enemy.
     pub fn spawn_chaser(&mut self, color: TeamColor, pos: Vec2) -> Entity {
+        let room = self.in_room();
         self.app
             .world_mut()
             .spawn((
+                room,
                 color,
                 EnemyType::Chaser,
+                Archetype("chaser".to_string()),
                 Position(pos),
                 Velocity(Vec2::ZERO),
                 Radius(ENEMY_RADIUS),
@@ -94,11 +354,14 @@ impl GameSim {
 
     /// Spawn an orbiter enemy.
     pub fn spawn_orbiter(&mut self, color: TeamColor, center: Vec2, radius: f32) -> Entity {
+        let room = self.in_room();
         self.app
             .world_mut()
             .spawn((
+                room,
                 color,
                 EnemyType::Orbiter,
+                Archetype("orbiter".to_string()),
                 Position(center + Vec2::new(radius, 0.0)),
                 Velocity(Vec2::ZERO),
                 Radius(ENEMY_RADIUS),
@@ -112,33 +375,714 @@ impl GameSim {
             .id()
     }
 
+    /// Spawn a splitter enemy.
+    pub fn spawn_splitter(&mut self, color: TeamColor, pos: Vec2, vel: Vec2) -> Entity {
+        let room = self.in_room();
+        self.app
+            .world_mut()
+            .spawn((
+                room,
+                color,
+                EnemyType::Splitter,
+                Archetype("splitter".to_string()),
+                Position(pos),
+                Velocity(vel),
+                Radius(SPLITTER_RADIUS),
+            ))
+            .id()
+    }
+
+    /// Spawn a shielder facing `facing` radians (counter-clockwise from +X).
+    pub fn spawn_shielder(&mut self, color: TeamColor, pos: Vec2, facing: f32) -> Entity {
+        let facing = Facing(facing);
+        let room = self.in_room();
+        self.app
+            .world_mut()
+            .spawn((
+                room,
+                color,
+                EnemyType::Shielder,
+                Archetype("shielder".to_string()),
+                Position(pos),
+                Velocity(facing.direction() * SHIELDER_SPEED),
+                Radius(SHIELDER_RADIUS),
+                facing,
+            ))
+            .id()
+    }
+
+    /// Current facing of a shielder, in radians.
+    pub fn facing(&self, entity: Entity) -> f32 {
+        self.app
+            .world()
+            .get::<Facing>(entity)
+            .expect("entity has no Facing")
+            .0
+    }
+
     /// Spawn an enemy by type.
     pub fn spawn_enemy(&mut self, etype: EnemyType, color: TeamColor, pos: Vec2) -> Entity {
         match etype {
             EnemyType::Drifter => self.spawn_drifter(color, pos, Vec2::new(DRIFTER_SPEED, 0.0)),
             EnemyType::Chaser => self.spawn_chaser(color, pos),
             EnemyType::Orbiter => self.spawn_orbiter(color, pos, ORBITER_RADIUS),
+            EnemyType::Splitter => self.spawn_splitter(color, pos, Vec2::new(SPLITTER_SPEED, 0.0)),
+            EnemyType::Shielder => self.spawn_shielder(color, pos, 0.0),
+            EnemyType::Boss => self.spawn_boss(color, pos),
+        }
+    }
+
+    /// Spawn the registry's boss, as a boss wave would but already arrived.
+    pub fn spawn_boss(&mut self, color: TeamColor, pos: Vec2) -> Entity {
+        let name = self
+            .app
+            .world()
+            .resource::<EnemyRegistry>()
+            .boss()
+            .expect("no boss archetype")
+            .name
+            .clone();
+        self.spawn_archetype(&name, color, pos)
+    }
+
+    /// Speed an enemy up (or slow it down) the way the difficulty director does.
+    pub fn set_speed_scale(&mut self, enemy: Entity, scale: f32) {
+        self.app
+            .world_mut()
+            .entity_mut(enemy)
+            .insert(SpeedScale(scale));
+    }
+
+    /// Hit points of a tough enemy; `None` for those that die in one hit.
+    pub fn enemy_health(&self, enemy: Entity) -> Option<EnemyHealth> {
+        self.app.world().get::<EnemyHealth>(enemy).copied()
+    }
+
+    /// Where a boss is in its fight.
+    pub fn boss_state(&self, boss: Entity) -> BossState {
+        *self
+            .app
+            .world()
+            .get::<BossState>(boss)
+            .expect("entity has no BossState")
+    }
+
+    /// Spawn a power-up pickup.
+    pub fn spawn_powerup(&mut self, powerup: PowerUp, pos: Vec2) -> Entity {
+        let room = self.in_room();
+        self.app
+            .world_mut()
+            .spawn((room, powerup, Position(pos), Radius(POWERUP_RADIUS)))
+            .id()
+    }
+
+    /// Seconds left on the active electric rope, if any.
+    pub fn rope_remaining(&mut self) -> Option<f32> {
+        self.app
+            .world_mut()
+            .query::<&ElectricRope>()
+            .iter(self.app.world())
+            .next()
+            .map(|rope| rope.remaining)
+    }
+
+    /// Spawn wave `wave` with the shared wave generator, at the director's
+    /// difficulty when waves run, and the room's RNG. Every batch arrives at
+    /// once (each enemy still telegraphed and clear of the living ships),
+    /// where the wave manager would stagger them.
+    pub fn spawn_wave(&mut self, wave: u32) {
+        let room = self.room();
+        let world = self.app.world_mut();
+        let registry = world.resource::<EnemyRegistry>().clone();
+        let difficulty = world
+            .get_resource::<Director>()
+            .map_or_else(Difficulty::default, |director| director.difficulty(room));
+        let mut rng = world
+            .entity_mut(room)
+            .take::<GameRng>()
+            .expect("room has no GameRng");
+        let groups = waves::procedural_wave(wave, difficulty, &registry, &mut rng);
+        world.entity_mut(room).insert(rng);
+        self.spawn_groups(&groups, difficulty.speed);
+    }
+
+    /// Spawn `count` enemies of the registry's archetype `name` in
+    /// `formation`, as a wave group would (telegraphed, clear of the living
+    /// ships, random colors unless the formation sets them).
+    pub fn spawn_formation(&mut self, name: &str, count: u32, formation: Formation) -> Vec<Entity> {
+        let group = SpawnGroup {
+            archetype: name.to_string(),
+            count,
+            color: GroupColor::Random,
+            formation,
+            delay: 0.0,
+        };
+        self.spawn_groups(&[group], 1.0)
+    }
+
+    /// Spawn `groups` right away with the room's RNG, ignoring their delays.
+    fn spawn_groups(&mut self, groups: &[SpawnGroup], speed_scale: f32) -> Vec<Entity> {
+        let room = self.room();
+        let ships = self.living_ship_positions();
+        let world = self.app.world_mut();
+        let mut rng = world
+            .entity_mut(room)
+            .take::<GameRng>()
+            .expect("room has no GameRng");
+        let bounds = world.resource::<ArenaBounds>().clone();
+        let registry = world.resource::<EnemyRegistry>().clone();
+        let mut enemies = Vec::new();
+        for group in groups {
+            enemies.extend(waves::spawn_group(
+                &mut world.commands(),
+                room,
+                group,
+                speed_scale,
+                &ships,
+                &registry,
+                &bounds,
+                &mut rng,
+            ));
+        }
+        world.entity_mut(room).insert(rng);
+        world.flush();
+        enemies
+    }
+
+    /// Positions of the living ships in the current room.
+    fn living_ship_positions(&mut self) -> Vec<Vec2> {
+        let room = self.room();
+        self.app
+            .world_mut()
+            .query_filtered::<(&Position, &Health, &InRoom), With<Ship>>()
+            .iter(self.app.world())
+            .filter(|(_, health, in_room)| health.alive && in_room.0 == room)
+            .map(|(pos, ..)| pos.0)
+            .collect()
+    }
+
+    /// Seconds until a telegraphed enemy arrives; `None` once it has.
+    pub fn spawn_warning(&self, enemy: Entity) -> Option<f32> {
+        self.app
+            .world()
+            .get::<SpawnWarning>(enemy)
+            .map(|warning| warning.0)
+    }
+
+    /// Run the wave manager and difficulty director in a local sim, as the
+    /// server does, playing `table`'s authored waves before procedural ones.
+    /// Wave 1 starts on the next tick.
+    pub fn run_waves(&mut self, table: WaveTable) {
+        assert!(
+            !self.is_networked(),
+            "networked sims run the server's own wave manager"
+        );
+        self.app.insert_resource(table);
+        WavesPlugin {
+            director: DirectorConfig::default(),
+        }
+        .build(&mut self.app);
+    }
+
+    /// Tune the difficulty director of a sim running waves.
+    pub fn set_director(&mut self, config: DirectorConfig) {
+        self.app.world_mut().resource_mut::<Director>().config = config;
+    }
+
+    /// The difficulty the local room's next waves spawn at.
+    pub fn difficulty(&mut self) -> Difficulty {
+        let room = self.room();
+        self.app.world().resource::<Director>().difficulty(room)
+    }
+
+    /// The calls the difficulty director has kept, oldest first.
+    pub fn director_decisions(&self) -> Vec<DirectorDecision> {
+        self.app
+            .world()
+            .resource::<Director>()
+            .decisions
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Spawn an enemy of the registry's archetype `name`, set up as waves
+    /// would with the room's RNG.
+    pub fn spawn_archetype(&mut self, name: &str, color: TeamColor, pos: Vec2) -> Entity {
+        let room = self.room();
+        let world = self.app.world_mut();
+        let archetype = world
+            .resource::<EnemyRegistry>()
+            .get(name)
+            .unwrap_or_else(|| panic!("no enemy archetype called {name}"))
+            .clone();
+        let mut rng = world
+            .entity_mut(room)
+            .take::<GameRng>()
+            .expect("room has no GameRng");
+        let bounds = world.resource::<ArenaBounds>().clone();
+        let enemy = enemies::spawn_enemy(
+            &mut world.commands(),
+            room,
+            &archetype,
+            color,
+            pos,
+            None,
+            1.0,
+            &bounds,
+            &mut rng,
+        );
+        world.entity_mut(room).insert(rng);
+        world.flush();
+        enemy
+    }
+
+    /// Play with `registry`'s enemy archetypes, on the server and every client.
+    pub fn set_enemies(&mut self, registry: EnemyRegistry) {
+        for client in &mut self.clients {
+            client.app.insert_resource(registry.clone());
         }
+        self.app.insert_resource(registry);
+    }
+
+    /// Name of the archetype an enemy was spawned as.
+    pub fn archetype(&self, entity: Entity) -> String {
+        self.app
+            .world()
+            .get::<Archetype>(entity)
+            .expect("entity has no Archetype")
+            .0
+            .clone()
+    }
+
+    /// Remove an entity from the authoritative world.
+    pub fn despawn(&mut self, entity: Entity) {
+        self.app.world_mut().despawn(entity);
     }
 
     /// Set a player's movement direction.
+    ///
+    /// In networked mode the input is fed to the owning client, which sends it
+    /// to the server like a real player would.
     pub fn set_input(&mut self, entity: Entity, direction: Vec2) {
+        if let Some(host) = self.host {
+            assert_eq!(self.owner(entity), host, "ship is not the host's");
+            let color = self.color(entity);
+            net::host_input(&mut self.app, color).direction = direction;
+            return;
+        }
+        if !self.clients.is_empty() {
+            let client = self.owning_client(entity);
+            let color = self.color(entity);
+            self.clients[client].input(color).direction = direction;
+            return;
+        }
+
         if let Some(mut action_state) = self
             .app
             .world_mut()
             .get_mut::<ActionState<PlayerInput>>(entity)
         {
-            action_state.0 = PlayerInput { direction };
+            action_state.0.direction = direction;
+        }
+    }
+
+    /// Set the direction a player aims and fires in; zero holds fire.
+    ///
+    /// In networked mode the aim is fed to the owning client, like
+    /// [`GameSim::set_input`].
+    pub fn set_aim(&mut self, entity: Entity, aim: Vec2) {
+        if let Some(host) = self.host {
+            assert_eq!(self.owner(entity), host, "ship is not the host's");
+            let color = self.color(entity);
+            net::host_input(&mut self.app, color).aim = aim;
+            return;
+        }
+        if !self.clients.is_empty() {
+            let client = self.owning_client(entity);
+            let color = self.color(entity);
+            self.clients[client].input(color).aim = aim;
+            return;
+        }
+
+        if let Some(mut action_state) = self
+            .app
+            .world_mut()
+            .get_mut::<ActionState<PlayerInput>>(entity)
+        {
+            action_state.0.aim = aim;
         }
     }
 
     /// Step the simulation forward by N ticks.
     pub fn step(&mut self, ticks: u32) {
         for _ in 0..ticks {
+            if !self.clients.is_empty() {
+                std::thread::sleep(TICK_DURATION);
+            }
             self.app.update();
+            for client in &mut self.clients {
+                client.app.update();
+            }
         }
     }
 
+    /// Step the simulation forward by `secs` of game time, whatever the tick rate.
+    pub fn step_secs(&mut self, secs: f32) {
+        let tick = self.app.world().resource::<Time<Fixed>>().timestep();
+        self.step((secs / tick.as_secs_f32()).round() as u32);
+    }
+
+    /// Whether ships belong to a client rather than to the test.
+    fn is_networked(&self) -> bool {
+        !self.clients.is_empty() || self.host.is_some()
+    }
+
+    /// Number of connected client Apps (0 in local and hosted mode).
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// The server entity of the ship controlled by client `index`.
+    pub fn player(&mut self, index: usize) -> Entity {
+        let link = self.clients[index].link;
+        self.app
+            .world_mut()
+            .query::<(Entity, &ControlledBy)>()
+            .iter(self.app.world())
+            .find(|(_, controlled)| controlled.owner == link)
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("client {index} controls no ship"))
+    }
+
+    /// Position of a ship as predicted by the client that controls it.
+    pub fn predicted_position(&mut self, ship: Entity) -> Vec2 {
+        let index = self.owning_client(ship);
+        let client = &mut self.clients[index];
+        let local = client.own_ship().expect("client has no predicted ship");
+        client
+            .app
+            .world()
+            .get::<Position>(local)
+            .expect("predicted ship has no Position")
+            .0
+    }
+
+    /// Whether the client that controls `ship` predicts it as alive.
+    pub fn predicted_alive(&mut self, ship: Entity) -> bool {
+        let index = self.owning_client(ship);
+        self.clients[index].own_ship_alive()
+    }
+
+    /// Position of a server entity as seen by client `index`, if replicated there.
+    pub fn client_position(&self, index: usize, entity: Entity) -> Option<Vec2> {
+        let client = &self.clients[index];
+        let local = client.local_entity(entity)?;
+        client.app.world().get::<Position>(local).map(|pos| pos.0)
+    }
+
+    /// Whether client `index` interpolates a server entity from the server's
+    /// updates, rather than predicting it.
+    pub fn client_interpolates(&self, index: usize, entity: Entity) -> bool {
+        let client = &self.clients[index];
+        client
+            .local_entity(entity)
+            .is_some_and(|local| client.app.world().get::<Interpolated>(local).is_some())
+    }
+
+    /// A ship's points as seen by client `index`, if its score has replicated.
+    pub fn client_score(&self, index: usize, ship: Entity) -> Option<u32> {
+        let client = &self.clients[index];
+        let local = client.local_entity(ship)?;
+        client
+            .app
+            .world()
+            .get::<Score>(local)
+            .map(|score| score.points)
+    }
+
+    /// Archetype of a server enemy as seen by client `index`, if replicated there.
+    pub fn client_archetype(&self, index: usize, enemy: Entity) -> Option<String> {
+        let client = &self.clients[index];
+        let local = client.local_entity(enemy)?;
+        client
+            .app
+            .world()
+            .get::<Archetype>(local)
+            .map(|archetype| archetype.0.clone())
+    }
+
+    /// A server boss's fight state and color as client `index` sees them, if
+    /// replicated there.
+    pub fn client_boss(&self, index: usize, boss: Entity) -> Option<(BossState, TeamColor)> {
+        let client = &self.clients[index];
+        let local = client.local_entity(boss)?;
+        let world = client.app.world();
+        Some((
+            *world.get::<BossState>(local)?,
+            *world.get::<TeamColor>(local)?,
+        ))
+    }
+
+    /// Seconds until a telegraphed server enemy arrives, as client `index`
+    /// sees it; `None` once it has or if it isn't replicated there.
+    pub fn client_spawn_warning(&self, index: usize, enemy: Entity) -> Option<f32> {
+        let client = &self.clients[index];
+        let local = client.local_entity(enemy)?;
+        client
+            .app
+            .world()
+            .get::<SpawnWarning>(local)
+            .map(|warning| warning.0)
+    }
+
+    /// How many shots client `index` shows in flight, pre-spawned or replicated.
+    pub fn client_projectile_count(&mut self, index: usize) -> usize {
+        let app = &mut self.clients[index].app;
+        app.world_mut()
+            .query_filtered::<(), With<Projectile>>()
+            .iter(app.world())
+            .count()
+    }
+
+    /// Current room's match state.
+    pub fn match_state(&mut self) -> MatchState {
+        let room = self.room();
+        *self
+            .app
+            .world()
+            .get::<MatchState>(room)
+            .expect("no match entity")
+    }
+
+    /// Lives the current room's team has left. Networked mode only.
+    pub fn lives(&mut self) -> u32 {
+        let room = self.room();
+        self.app
+            .world()
+            .get::<TeamLives>(room)
+            .expect("only networked sims track lives")
+            .0
+    }
+
+    /// Overwrite the current room's remaining lives on the server.
+    pub fn set_lives(&mut self, lives: u32) {
+        let room = self.room();
+        self.app
+            .world_mut()
+            .get_mut::<TeamLives>(room)
+            .expect("only networked sims track lives")
+            .0 = lives;
+    }
+
+    /// Switch how ships handle in the current room. In networked mode the
+    /// server's match entity changes and clients pick it up through
+    /// replication.
+    pub fn set_handling(&mut self, handling: ShipHandling) {
+        let room = self.room();
+        self.app.world_mut().entity_mut(room).insert(handling);
+    }
+
+    /// How ships handle in the current room, as client `index` sees it.
+    pub fn client_handling(&mut self, index: usize) -> Option<ShipHandling> {
+        let app = &mut self.clients[index].app;
+        app.world_mut()
+            .query::<&ShipHandling>()
+            .single(app.world())
+            .ok()
+            .copied()
+    }
+
+    /// Make the room called `name` the one room-scoped helpers act on.
+    pub fn select_room(&mut self, name: &str) {
+        self.room = Some(self.room_named(name));
+    }
+
+    /// The server's match entity for the room called `name`.
+    pub fn room_named(&mut self, name: &str) -> Entity {
+        self.find_room(name)
+            .unwrap_or_else(|| panic!("no room called {name}"))
+    }
+
+    /// Whether the server has a room called `name` open.
+    pub fn room_open(&mut self, name: &str) -> bool {
+        self.find_room(name).is_some()
+    }
+
+    fn find_room(&mut self, name: &str) -> Option<Entity> {
+        self.app
+            .world_mut()
+            .query::<(Entity, &RoomName)>()
+            .iter(self.app.world())
+            .find(|(_, room)| room.0 == name)
+            .map(|(entity, _)| entity)
+    }
+
+    /// Players seated in the room called `name`, in joining order.
+    pub fn room_players(&mut self, name: &str) -> Vec<RoomPlayer> {
+        let room = self.room_named(name);
+        self.app
+            .world()
+            .get::<RoomPlayers>(room)
+            .expect("room has no roster")
+            .0
+            .clone()
+    }
+
+    /// Have client `index` ask for a seat in the room called `name`.
+    pub fn join_room(&mut self, index: usize, name: &str) {
+        self.clients[index].join_room(name, None, false);
+    }
+
+    /// Have client `index` ask for a seat in the room called `name`, flying
+    /// `color`.
+    pub fn join_room_as(&mut self, index: usize, name: &str, color: TeamColor) {
+        self.clients[index].join_room(name, Some(color), false);
+    }
+
+    /// Have client `index` ask for both seats in the room called `name`, to
+    /// fly both ships.
+    pub fn join_solo(&mut self, index: usize, name: &str) {
+        self.clients[index].join_room(name, None, true);
+    }
+
+    /// Every request the server has turned down for client `index`.
+    pub fn rejections(&self, index: usize) -> Vec<Rejected> {
+        self.clients[index].rejections()
+    }
+
+    /// Have client `index` ask for `color` while its room is in the lobby.
+    pub fn choose_color(&mut self, index: usize, color: TeamColor) {
+        self.clients[index].choose_color(color);
+    }
+
+    /// Have client `index` flag itself ready (or not) in its room.
+    pub fn set_ready(&mut self, index: usize, ready: bool) {
+        self.clients[index].set_ready(ready);
+    }
+
+    /// Drop client `index`'s connection, as if it quit. Returns once the
+    /// server has let go of it.
+    pub fn disconnect(&mut self, index: usize) {
+        self.clients[index].disconnect();
+        let link = self.clients[index].link;
+        self.wait_for(|sim| sim.app.world().get_entity(link).is_err());
+    }
+
+    /// Connect client `index` again, under the same client id, as a fresh
+    /// App. Returns once it is connected; it has no seat unless the server
+    /// gave it back its parked ship.
+    pub fn reconnect(&mut self, index: usize) {
+        let (id, condition) = (self.clients[index].id, self.clients[index].condition);
+        self.clients[index] = net::connect_client(&mut self.app, id, condition);
+        self.wait_for(|sim| sim.clients[index].is_connected());
+    }
+
+    /// Whether a ship is parked, its client having dropped out.
+    pub fn is_parked(&self, ship: Entity) -> bool {
+        self.app.world().get::<Parked>(ship).is_some()
+    }
+
+    /// Whether client `index` has a seat (and so a ship) in some room.
+    pub fn seated(&mut self, index: usize) -> bool {
+        let link = self.clients[index].link;
+        self.app
+            .world_mut()
+            .query::<&ControlledBy>()
+            .iter(self.app.world())
+            .any(|controlled| controlled.owner == link)
+    }
+
+    /// A ship's team color, as the server sees it.
+    pub fn color(&self, entity: Entity) -> TeamColor {
+        *self
+            .app
+            .world()
+            .get::<TeamColor>(entity)
+            .expect("entity has no TeamColor")
+    }
+
+    /// The room room-scoped helpers act on: the selected one, or else the
+    /// server's only room.
+    fn room(&mut self) -> Entity {
+        if let Some(room) = self.room {
+            return room;
+        }
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, With<RoomName>>()
+            .single(self.app.world())
+            .expect("select_room first when the server hosts several rooms")
+    }
+
+    fn in_room(&mut self) -> InRoom {
+        InRoom(self.room())
+    }
+
+    /// Match state as client `index` last saw it.
+    pub fn client_match_state(&mut self, index: usize) -> Option<MatchState> {
+        let app = &mut self.clients[index].app;
+        app.world_mut()
+            .query::<&MatchState>()
+            .single(app.world())
+            .ok()
+            .copied()
+    }
+
+    /// Have client `index` ask the server for a rematch.
+    pub fn request_rematch(&mut self, index: usize) {
+        self.clients[index].request_rematch();
+    }
+
+    /// The server's leaderboard. Networked mode only.
+    pub fn leaderboard(&self) -> Vec<MatchResult> {
+        self.app
+            .world()
+            .get_resource::<Leaderboard>()
+            .expect("only networked sims keep a leaderboard")
+            .entries()
+            .to_vec()
+    }
+
+    /// The leaderboard client `index` last received, if any.
+    pub fn client_leaderboard(&self, index: usize) -> Option<Vec<MatchResult>> {
+        self.clients[index].leaderboard()
+    }
+
+    /// Whether a server entity currently exists on client `index`.
+    pub fn replicated_to(&self, index: usize, entity: Entity) -> bool {
+        self.clients[index]
+            .local_entity(entity)
+            .is_some_and(|local| self.clients[index].app.world().get_entity(local).is_ok())
+    }
+
+    /// Ships the host steers in hosted mode, in spawn order.
+    pub fn host_ships(&mut self) -> Vec<Entity> {
+        self.app
+            .world_mut()
+            .query_filtered::<Entity, (With<Ship>, With<InputMarker<PlayerInput>>)>()
+            .iter(self.app.world())
+            .collect()
+    }
+
+    /// The server entity of the link controlling `ship`.
+    fn owner(&self, ship: Entity) -> Entity {
+        self.app
+            .world()
+            .get::<ControlledBy>(ship)
+            .expect("entity is not controlled by a client")
+            .owner
+    }
+
+    /// Index of the client whose link controls `ship` on the server.
+    fn owning_client(&self, ship: Entity) -> usize {
+        let owner = self.owner(ship);
+        self.clients
+            .iter()
+            .position(|client| client.link == owner)
+            .expect("ship's controller is not a sim client")
+    }
+
     /// Get entity's position.
     pub fn position(&self, entity: Entity) -> Vec2 {
         self.app
@@ -184,9 +1128,92 @@ impl GameSim {
             .0
     }
 
-    /// Get the current wave state.
-    pub fn wave_state(&self) -> WaveState {
-        self.app.world().resource::<WaveState>().clone()
+    /// Get an entity's collision radius.
+    pub fn radius(&self, entity: Entity) -> f32 {
+        self.app
+            .world()
+            .get::<Radius>(entity)
+            .expect("entity has no Radius")
+            .0
+    }
+
+    /// Every live enemy entity, in spawn order.
+    pub fn enemy_entities(&mut self) -> Vec<Entity> {
+        let mut enemies: Vec<_> = self
+            .app
+            .world_mut()
+            .query_filtered::<Entity, With<EnemyType>>()
+            .iter(self.app.world())
+            .collect();
+        enemies.sort();
+        enemies
+    }
+
+    /// Every shot in flight, in firing order.
+    pub fn projectiles(&mut self) -> Vec<Entity> {
+        let mut projectiles: Vec<_> = self
+            .app
+            .world_mut()
+            .query_filtered::<Entity, With<Projectile>>()
+            .iter(self.app.world())
+            .collect();
+        projectiles.sort();
+        projectiles
+    }
+
+    /// Points a ship has scored, as the server sees them.
+    pub fn score(&self, ship: Entity) -> u32 {
+        self.ship_score(ship).points
+    }
+
+    /// Multiplier the ship's next kill will earn.
+    pub fn multiplier(&self, ship: Entity) -> u32 {
+        self.ship_score(ship).multiplier()
+    }
+
+    fn ship_score(&self, ship: Entity) -> &Score {
+        self.app
+            .world()
+            .get::<Score>(ship)
+            .expect("entity has no Score")
+    }
+
+    /// Get the current room's wave state.
+    pub fn wave_state(&mut self) -> WaveState {
+        let room = self.room();
+        self.app
+            .world()
+            .get::<WaveState>(room)
+            .expect("room has no WaveState")
+            .clone()
+    }
+
+    /// Type, color and position of every enemy, in spawn order.
+    pub fn enemies(&mut self) -> Vec<(EnemyType, TeamColor, Vec2)> {
+        let mut enemies: Vec<_> = self
+            .app
+            .world_mut()
+            .query::<(Entity, &EnemyType, &TeamColor, &Position)>()
+            .iter(self.app.world())
+            .map(|(entity, etype, color, pos)| (entity, (*etype, *color, pos.0)))
+            .collect();
+        enemies.sort_by_key(|(entity, _)| *entity);
+        enemies.into_iter().map(|(_, enemy)| enemy).collect()
+    }
+
+    /// Seed of the current room's authoritative match RNG.
+    pub fn rng_seed(&mut self) -> u64 {
+        let room = self.room();
+        self.app
+            .world()
+            .get::<GameRng>(room)
+            .expect("room has no GameRng")
+            .seed()
+    }
+
+    /// Seed of the match RNG on client `index`.
+    pub fn client_rng_seed(&mut self, index: usize) -> u64 {
+        self.clients[index].rng_seed()
     }
 
     /// Count remaining enemies.
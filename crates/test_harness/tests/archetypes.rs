@@ -0,0 +1,253 @@
+use bevy::math::Vec2;
+use schizoid_shared::archetypes::{Behaviour, EnemyArchetype, EnemyRegistry, Shape};
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+use std::path::Path;
+
+fn sprinter() -> EnemyArchetype {
+    EnemyArchetype {
+        name: "sprinter".to_string(),
+        behaviour: Behaviour::Chaser,
+        speed: 400.0,
+        radius: 7.0,
+        points: 99,
+        hit_points: 1,
+        spawn_weight: 1.0,
+        weight_per_wave: 0.0,
+        max_weight: None,
+        first_wave: 1,
+        shape: Some(Shape::Polygon(4)),
+    }
+}
+
+/// The built-in archetypes with a sprinter added, as a designer would.
+fn with_sprinter() -> EnemyRegistry {
+    let mut registry = EnemyRegistry::default();
+    registry.archetypes.push(sprinter());
+    registry
+}
+
+#[test]
+fn bundled_file_matches_builtin_enemies() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets/enemies.ron");
+    assert_eq!(EnemyRegistry::load(&path), EnemyRegistry::default());
+}
+
+#[test]
+fn missing_file_falls_back_to_builtin_enemies() {
+    let registry = EnemyRegistry::load(Path::new("no/such/enemies.ron"));
+    assert_eq!(registry, EnemyRegistry::default());
+}
+
+#[test]
+fn undrawable_shapes_fall_back_to_the_usual_look() {
+    let path = std::env::temp_dir().join(format!("schizoid-enemies-{}.ron", std::process::id()));
+    let ron = r#"(archetypes: [
+        (name: "speck", behaviour: Drifter, speed: 100.0, radius: 0.0, points: 1,
+            spawn_weight: 1.0, shape: Some(Polygon(4))),
+        (name: "sliver", behaviour: Chaser, speed: 100.0, radius: 8.0, points: 1,
+            spawn_weight: 1.0, shape: Some(Polygon(2))),
+    ])"#;
+    std::fs::write(&path, ron).unwrap();
+    let registry = EnemyRegistry::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let speck = registry.get("speck").expect("The file should still load");
+    assert_eq!(speck.radius, ENEMY_RADIUS, "Zero radius is replaced");
+    assert_eq!(speck.shape, Some(Shape::Polygon(4)));
+    let sliver = registry.get("sliver").unwrap();
+    assert_eq!(sliver.radius, 8.0);
+    assert_eq!(sliver.shape, None, "Two sides can't make a polygon");
+}
+
+#[test]
+fn bad_weights_are_zeroed() {
+    let path = std::env::temp_dir().join(format!("schizoid-weights-{}.ron", std::process::id()));
+    let ron = r#"(archetypes: [
+        (name: "negative", behaviour: Drifter, speed: 100.0, radius: 8.0, points: 1,
+            spawn_weight: -5.0, weight_per_wave: -1.0),
+        (name: "nan", behaviour: Drifter, speed: 100.0, radius: 8.0, points: 1,
+            spawn_weight: NaN, max_weight: Some(-1.0)),
+        (name: "fine", behaviour: Drifter, speed: 100.0, radius: 8.0, points: 1,
+            spawn_weight: 1.0),
+    ])"#;
+    std::fs::write(&path, ron).unwrap();
+    let registry = EnemyRegistry::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let negative = registry
+        .get("negative")
+        .expect("The file should still load");
+    assert_eq!(negative.spawn_weight, 0.0);
+    assert_eq!(negative.weight_per_wave, 0.0);
+    let nan = registry.get("nan").unwrap();
+    assert_eq!(nan.spawn_weight, 0.0);
+    assert_eq!(nan.max_weight, Some(0.0));
+    for roll in [0.0, 0.5, 0.99] {
+        assert_eq!(
+            registry
+                .pick(1, roll, 1.0)
+                .map(|archetype| archetype.name.as_str()),
+            Some("fine"),
+            "Only the archetype with a real weight should spawn"
+        );
+    }
+}
+
+#[test]
+fn waves_spawn_only_archetypes_with_weight() {
+    let mut sim = GameSim::with_seed(3);
+    sim.set_enemies(EnemyRegistry {
+        archetypes: vec![sprinter()],
+    });
+
+    sim.spawn_wave(1);
+
+    let enemies = sim.enemy_entities();
+    assert_eq!(
+        enemies.len(),
+        5,
+        "Wave size should not depend on archetypes"
+    );
+    for enemy in enemies {
+        assert_eq!(sim.archetype(enemy), "sprinter");
+        assert_eq!(sim.radius(enemy), 7.0);
+    }
+}
+
+#[test]
+fn archetypes_wait_for_their_first_wave() {
+    let mut late = sprinter();
+    late.first_wave = 3;
+    let mut registry = EnemyRegistry::default();
+    registry.archetypes.push(late);
+    let mut sim = GameSim::with_seed(11);
+    sim.set_enemies(registry);
+
+    sim.spawn_wave(1);
+    sim.spawn_wave(2);
+
+    let enemies = sim.enemy_entities();
+    assert!(
+        enemies
+            .iter()
+            .all(|&enemy| sim.archetype(enemy) != "sprinter"),
+        "Sprinters should not show up before wave 3"
+    );
+    assert!(
+        enemies
+            .iter()
+            .all(|&enemy| sim.archetype(enemy) != "splitter_child"),
+        "Zero-weight archetypes should never spawn in waves"
+    );
+}
+
+#[test]
+fn weights_grow_per_wave_up_to_the_cap() {
+    let chaser = EnemyRegistry::default().get("chaser").cloned().unwrap();
+    assert!((chaser.weight(1) - 0.1).abs() < 1e-6);
+    assert!((chaser.weight(3) - 0.3).abs() < 1e-6);
+    assert!((chaser.weight(20) - 0.6).abs() < 1e-6);
+}
+
+#[test]
+fn archetype_speed_drives_its_behaviour() {
+    let mut sim = GameSim::new();
+    sim.set_enemies(with_sprinter());
+    sim.spawn_player(TeamColor::Blue, Vec2::new(-400.0, 0.0));
+    let sprinter = sim.spawn_archetype("sprinter", TeamColor::Red, Vec2::new(400.0, 0.0));
+    let chaser = sim.spawn_chaser(TeamColor::Red, Vec2::new(400.0, 100.0));
+
+    sim.step(2);
+
+    assert!((sim.velocity(sprinter).length() - 400.0).abs() < 1e-3);
+    assert!((sim.velocity(chaser).length() - CHASER_SPEED).abs() < 1e-3);
+}
+
+#[test]
+fn kills_score_the_archetypes_points() {
+    let mut sim = GameSim::new();
+    sim.set_enemies(with_sprinter());
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let sprinter = sim.spawn_archetype("sprinter", TeamColor::Red, Vec2::new(15.0, 0.0));
+
+    sim.step(1);
+
+    assert!(!sim.entity_exists(sprinter));
+    assert_eq!(sim.score(red), 99);
+}
+
+#[test]
+fn splitters_split_into_their_child_archetype() {
+    let mut registry = with_sprinter();
+    let mut splitter = registry.get("splitter").cloned().unwrap();
+    splitter.name = "sprinter_nest".to_string();
+    splitter.behaviour = Behaviour::Splitter {
+        child: "sprinter".to_string(),
+    };
+    registry.archetypes.push(splitter);
+    let mut sim = GameSim::new();
+    sim.set_enemies(registry);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let nest = sim.spawn_archetype("sprinter_nest", TeamColor::Red, Vec2::new(60.0, 0.0));
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    assert!(!sim.entity_exists(nest));
+    let children = sim.enemy_entities();
+    assert_eq!(children.len(), 2);
+    for child in children {
+        assert_eq!(sim.archetype(child), "sprinter");
+        assert_eq!(sim.radius(child), 7.0);
+    }
+}
+
+#[test]
+fn tough_children_take_several_hits() {
+    let mut registry = with_sprinter();
+    registry
+        .archetypes
+        .iter_mut()
+        .find(|archetype| archetype.name == "sprinter")
+        .unwrap()
+        .hit_points = 3;
+    let mut splitter = registry.get("splitter").cloned().unwrap();
+    splitter.name = "sprinter_nest".to_string();
+    splitter.behaviour = Behaviour::Splitter {
+        child: "sprinter".to_string(),
+    };
+    registry.archetypes.push(splitter);
+    let mut sim = GameSim::new();
+    sim.set_enemies(registry);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_archetype("sprinter_nest", TeamColor::Red, Vec2::new(60.0, 0.0));
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    let children = sim.enemy_entities();
+    assert_eq!(children.len(), 2);
+    for child in children {
+        assert_eq!(
+            sim.enemy_health(child).map(|health| health.current),
+            Some(3),
+            "Children should get their archetype's hit points"
+        );
+    }
+}
+
+#[test]
+fn archetype_replicates_to_clients() {
+    let mut sim = GameSim::networked(1);
+    sim.set_enemies(with_sprinter());
+    let sprinter = sim.spawn_archetype("sprinter", TeamColor::Red, Vec2::new(400.0, 300.0));
+
+    sim.step(20);
+
+    assert!(sim.replicated_to(0, sprinter));
+    assert_eq!(
+        sim.client_archetype(0, sprinter).as_deref(),
+        Some("sprinter")
+    );
+}
@@ -0,0 +1,164 @@
+use bevy::math::Vec2;
+use schizoid_shared::archetypes::EnemyRegistry;
+use schizoid_shared::components::*;
+use schizoid_shared::enemies::formations::Formation;
+use schizoid_shared::waves::{AuthoredWave, GroupColor, SpawnGroup, WaveTable};
+use schizoid_test_harness::sim::GameSim;
+use std::path::Path;
+
+fn group(archetype: &str, count: u32, delay: f32) -> SpawnGroup {
+    SpawnGroup {
+        archetype: archetype.to_string(),
+        count,
+        color: GroupColor::Random,
+        formation: Formation::Edges,
+        delay,
+    }
+}
+
+fn table(waves: Vec<Vec<SpawnGroup>>) -> WaveTable {
+    WaveTable {
+        waves: waves
+            .into_iter()
+            .map(|groups| AuthoredWave {
+                groups,
+                breather: WAVE_BREATHER,
+            })
+            .collect(),
+    }
+}
+
+fn clear_enemies(sim: &mut GameSim) {
+    for enemy in sim.enemy_entities() {
+        sim.despawn(enemy);
+    }
+}
+
+#[test]
+fn bundled_wave_file_uses_known_archetypes() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets/waves.ron");
+    let registry = EnemyRegistry::default();
+    let table = WaveTable::load(&path, &registry);
+
+    assert!(!table.waves.is_empty(), "The bundled wave file should load");
+    for wave in &table.waves {
+        for group in &wave.groups {
+            assert!(registry.get(&group.archetype).is_some());
+        }
+    }
+}
+
+#[test]
+fn authored_wave_spawns_its_groups() {
+    let mut sim = GameSim::with_seed(5);
+    sim.run_waves(table(vec![vec![SpawnGroup {
+        color: GroupColor::Red,
+        formation: Formation::Cluster {
+            at: Vec2::new(100.0, 50.0),
+            spread: 0.0,
+        },
+        ..group("chaser", 3, 0.0)
+    }]]));
+
+    sim.step(1);
+
+    assert_eq!(sim.wave_state().current_wave, 1);
+    let enemies = sim.enemies();
+    assert_eq!(enemies.len(), 3);
+    for (kind, color, pos) in enemies {
+        assert_eq!(kind, EnemyType::Chaser);
+        assert_eq!(color, TeamColor::Red);
+        assert_eq!(pos, Vec2::new(100.0, 50.0));
+    }
+}
+
+#[test]
+fn alternating_groups_split_colors_evenly() {
+    let mut sim = GameSim::new();
+    sim.run_waves(table(vec![vec![SpawnGroup {
+        color: GroupColor::Alternating,
+        ..group("drifter", 4, 0.0)
+    }]]));
+
+    sim.step(1);
+
+    let reds = sim
+        .enemies()
+        .iter()
+        .filter(|(_, color, _)| *color == TeamColor::Red)
+        .count();
+    assert_eq!(reds, 2);
+    assert_eq!(sim.enemy_count(), 4);
+}
+
+#[test]
+fn delayed_group_arrives_later_and_holds_the_wave_open() {
+    let mut sim = GameSim::new();
+    sim.run_waves(table(vec![vec![
+        group("drifter", 1, 0.0),
+        group("drifter", 2, 1.0),
+    ]]));
+
+    sim.step(1);
+    assert_eq!(sim.enemy_count(), 1);
+
+    clear_enemies(&mut sim);
+    sim.step_secs(0.5);
+    let wave = sim.wave_state();
+    assert!(
+        wave.active && wave.current_wave == 1,
+        "Wave should wait for its delayed group, got {wave:?}"
+    );
+    assert_eq!(sim.enemy_count(), 0);
+
+    sim.step_secs(0.6);
+    assert_eq!(sim.enemy_count(), 2, "Delayed group should have arrived");
+}
+
+#[test]
+fn authored_breather_sets_the_rest_between_waves() {
+    let mut authored = table(vec![
+        vec![group("drifter", 1, 0.0)],
+        vec![group("drifter", 1, 0.0)],
+    ]);
+    authored.waves[0].breather = 0.5;
+    let mut sim = GameSim::new();
+    sim.run_waves(authored);
+    sim.step(1);
+
+    clear_enemies(&mut sim);
+    sim.step_secs(0.3);
+    assert_eq!(sim.wave_state().current_wave, 1, "Still resting");
+
+    sim.step_secs(0.4);
+    assert_eq!(sim.wave_state().current_wave, 2);
+    assert_eq!(sim.enemy_count(), 1);
+}
+
+#[test]
+fn procedural_waves_take_over_after_the_table() {
+    let mut authored = table(vec![vec![group("chaser", 1, 0.0)]]);
+    authored.waves[0].breather = 0.1;
+    let mut sim = GameSim::with_seed(9);
+    sim.run_waves(authored);
+    sim.step(1);
+
+    clear_enemies(&mut sim);
+    sim.step_secs(0.3);
+
+    assert_eq!(sim.wave_state().current_wave, 2);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+    assert_eq!(sim.enemy_count(), 2 * 3 + 2, "Wave 2 should be procedural");
+}
+
+#[test]
+fn empty_table_plays_procedural_waves() {
+    let mut sim = GameSim::with_seed(9);
+    sim.run_waves(WaveTable::default());
+
+    sim.step(1);
+    assert_eq!(sim.wave_state().current_wave, 1);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+
+    assert_eq!(sim.enemy_count(), 5);
+}
@@ -0,0 +1,124 @@
+use bevy::input::gamepad::{Gamepad, GamepadAxis};
+use bevy::math::Vec2;
+use schizoid_client::bindings::{Bindings, BindingsFile};
+use std::path::PathBuf;
+
+/// A path for one test's bindings file, holding `ron` if given.
+fn bindings_file(test: &str, ron: Option<&str>) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "schizoid-bindings-{test}-{}.ron",
+        std::process::id()
+    ));
+    match ron {
+        Some(ron) => std::fs::write(&path, ron).unwrap(),
+        None => {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+    path
+}
+
+/// A gamepad with its left stick pushed to `position`.
+fn left_stick_at(position: Vec2) -> Gamepad {
+    let mut gamepad = Gamepad::default();
+    gamepad
+        .analog_mut()
+        .set(GamepadAxis::LeftStickX, position.x);
+    gamepad
+        .analog_mut()
+        .set(GamepadAxis::LeftStickY, position.y);
+    gamepad
+}
+
+#[test]
+fn stick_ignores_the_deadzone_and_rescales_the_rest() {
+    let bindings = Bindings::default();
+    let stick = bindings.stick;
+
+    assert_eq!(
+        bindings.read_stick(&stick, &left_stick_at(Vec2::new(0.1, 0.1))),
+        None
+    );
+    let full = bindings
+        .read_stick(&stick, &left_stick_at(Vec2::new(1.0, 0.0)))
+        .unwrap();
+    assert!((full - Vec2::new(1.0, 0.0)).length() < 1e-5);
+
+    // Halfway between the deadzone edge and full travel
+    let half = bindings
+        .read_stick(&stick, &left_stick_at(Vec2::new(0.0, -0.6)))
+        .unwrap();
+    assert!((half - Vec2::new(0.0, -0.5)).length() < 1e-5, "{half}");
+}
+
+#[test]
+fn response_curve_softens_small_pushes() {
+    let bindings = Bindings {
+        deadzone: 0.0,
+        response_curve: 2.0,
+        ..Bindings::default()
+    };
+
+    let half = bindings
+        .read_stick(&bindings.stick, &left_stick_at(Vec2::new(0.5, 0.0)))
+        .unwrap();
+    assert!((half - Vec2::new(0.25, 0.0)).length() < 1e-5, "{half}");
+    let beyond = bindings
+        .read_stick(&bindings.stick, &left_stick_at(Vec2::new(1.0, 1.0)))
+        .unwrap();
+    assert!(
+        (beyond.length() - 1.0).abs() < 1e-5,
+        "Diagonals cap at full travel"
+    );
+}
+
+#[test]
+fn missing_fields_keep_their_defaults() {
+    let path = bindings_file("partial", Some("(response_curve: 1.5)"));
+    let bindings = BindingsFile::new(path.clone()).load().unwrap();
+
+    assert_eq!(bindings.response_curve, 1.5);
+    assert_eq!(
+        Bindings {
+            response_curve: 1.0,
+            ..bindings
+        },
+        Bindings::default()
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn actions_describe_what_they_are_bound_to() {
+    let path = bindings_file(
+        "describe",
+        Some("(rematch: (keys: [KeyT], buttons: []), swap_color: (keys: [], buttons: []))"),
+    );
+    let bindings = BindingsFile::new(path.clone()).load().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(bindings.ready.describe(), "Enter, Start");
+    assert_eq!(bindings.rematch.describe(), "KeyT");
+    assert_eq!(bindings.swap_color.describe(), "-");
+}
+
+#[test]
+fn bad_stick_settings_fall_back_to_defaults() {
+    let path = bindings_file("bad", Some("(deadzone: 1.5, response_curve: -1.0)"));
+    let bindings = BindingsFile::new(path.clone()).load().unwrap();
+
+    assert_eq!(bindings.deadzone, Bindings::default().deadzone);
+    assert_eq!(bindings.response_curve, Bindings::default().response_curve);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn unreadable_or_missing_files_load_nothing() {
+    let garbage = bindings_file("garbage", Some("not ron at all"));
+    assert_eq!(BindingsFile::new(garbage.clone()).load(), None);
+    std::fs::remove_file(&garbage).unwrap();
+
+    let missing = bindings_file("missing", None);
+    assert_eq!(BindingsFile::new(missing.clone()).load(), None);
+    assert!(!missing.exists(), "Loading never writes the file");
+}
@@ -0,0 +1,194 @@
+use bevy::math::Vec2;
+use schizoid_shared::archetypes::{Behaviour, EnemyRegistry};
+use schizoid_shared::components::*;
+use schizoid_shared::waves;
+use schizoid_test_harness::sim::GameSim;
+
+/// The built-in enemies with a boss that stays in its first phase and goes
+/// down in `hit_points` hits.
+fn with_short_boss_fight(hit_points: u32) -> EnemyRegistry {
+    let mut registry = EnemyRegistry::default();
+    for archetype in &mut registry.archetypes {
+        if let Behaviour::Boss { phases } = &mut archetype.behaviour {
+            phases.truncate(1);
+            archetype.hit_points = hit_points;
+        }
+    }
+    registry
+}
+
+#[test]
+fn same_color_shots_wear_the_boss_down() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(200.0, 0.0));
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step_secs(0.5);
+
+    let health = sim.enemy_health(boss).expect("bosses have hit points");
+    assert!(sim.entity_exists(boss), "One volley should not kill a boss");
+    assert_eq!(health.max, BOSS_HIT_POINTS);
+    assert!(
+        health.current < health.max,
+        "Shots should chip the boss's hit points"
+    );
+    assert_eq!(sim.score(red), 0, "Only the kill scores");
+}
+
+#[test]
+fn boss_dies_after_its_last_hit_point_and_scores() {
+    let mut sim = GameSim::new();
+    sim.set_enemies(with_short_boss_fight(3));
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(200.0, 0.0));
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step_secs(1.0);
+
+    assert!(!sim.entity_exists(boss), "Three hits should finish it");
+    assert_eq!(sim.score(red), EnemyType::Boss.points());
+}
+
+#[test]
+fn same_color_contact_knocks_the_ship_back() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(80.0, 0.0));
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(15);
+
+    assert!(sim.is_alive(red), "Ramming a same-color boss is safe");
+    assert!(sim.entity_exists(boss), "One ram should not kill a boss");
+    let health = sim.enemy_health(boss).unwrap();
+    assert!(health.current < health.max, "The ram should still hurt it");
+    assert!(sim.distance(red, boss) >= SHIP_RADIUS + BOSS_RADIUS);
+}
+
+#[test]
+fn opposite_color_contact_kills_the_ship() {
+    let mut sim = GameSim::new();
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::ZERO);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(80.0, 0.0));
+
+    sim.step_secs(1.0);
+
+    assert!(sim.is_dead(blue), "The boss should run the blue ship down");
+    assert_eq!(sim.enemy_health(boss).unwrap().current, BOSS_HIT_POINTS);
+}
+
+#[test]
+fn boss_stalks_then_charges() {
+    let mut sim = GameSim::new();
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::new(400.0, 0.0));
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(-400.0, 0.0));
+
+    sim.step_secs(1.0);
+    let stalking = sim.velocity(boss);
+    assert!((stalking.length() - BOSS_SPEED).abs() < 1e-3);
+    assert!(stalking.x > 0.0, "The boss should head for the blue ship");
+
+    // First phase charges every four seconds
+    sim.step_secs(3.1);
+    let charging = sim.velocity(boss);
+    assert!(
+        (charging.length() - BOSS_SPEED * BOSS_CHARGE_SPEEDUP).abs() < 1e-3,
+        "Boss should be charging, velocity: {charging}"
+    );
+    assert!(sim.boss_state(boss).charging > 0.0);
+
+    sim.step_secs(BOSS_CHARGE_TIME);
+    assert!((sim.velocity(boss).length() - BOSS_SPEED).abs() < 1e-3);
+    assert!(sim.is_alive(blue));
+}
+
+#[test]
+fn losing_a_third_flips_color_and_calls_minions() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(300.0, 0.0));
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    for _ in 0..600 {
+        if sim.color(boss) == TeamColor::Blue {
+            break;
+        }
+        sim.step(1);
+    }
+
+    assert_eq!(sim.color(boss), TeamColor::Blue, "Phase two flips the boss");
+    assert_eq!(sim.boss_state(boss).phase, 1);
+    let health = sim.enemy_health(boss).unwrap();
+    assert!(health.fraction() <= 0.66);
+    let minions: Vec<_> = sim
+        .enemy_entities()
+        .into_iter()
+        .filter(|&enemy| enemy != boss)
+        .collect();
+    assert_eq!(minions.len(), 4, "Phase two calls in four drifters");
+    for minion in minions {
+        assert_eq!(sim.archetype(minion), "drifter");
+        assert!(
+            sim.spawn_warning(minion).is_some(),
+            "Minions are telegraphed"
+        );
+    }
+}
+
+#[test]
+fn the_rope_passes_through_bosses() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+    sim.spawn_player(TeamColor::Blue, Vec2::new(300.0, 0.0));
+    let boss = sim.spawn_boss(TeamColor::Blue, Vec2::new(0.0, 10.0));
+
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(-300.0, 0.0));
+    sim.step(20);
+
+    assert!(sim.rope_remaining().is_some(), "The rope should be up");
+    assert!(sim.entity_exists(boss), "The beam should not kill a boss");
+    assert_eq!(
+        sim.enemy_health(boss).unwrap().current,
+        BOSS_HIT_POINTS,
+        "The beam should not chip a boss from a distance"
+    );
+}
+
+#[test]
+fn every_fifth_wave_is_a_boss_fight() {
+    let registry = EnemyRegistry::default();
+
+    for wave in 1..=10 {
+        let groups = waves::boss_wave(wave, &registry);
+        assert_eq!(groups.is_some(), wave % BOSS_WAVE_INTERVAL == 0);
+        if let Some(groups) = groups {
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].archetype, "boss");
+            assert_eq!(groups[0].count, 1);
+        }
+    }
+
+    let mut no_boss = registry.clone();
+    no_boss
+        .archetypes
+        .retain(|archetype| archetype.name != "boss");
+    assert_eq!(
+        waves::boss_wave(5, &no_boss),
+        None,
+        "Without a boss, wave 5 stays procedural"
+    );
+}
+
+#[test]
+fn boss_fight_replicates_to_clients() {
+    let mut sim = GameSim::networked(1);
+    let boss = sim.spawn_boss(TeamColor::Red, Vec2::new(300.0, 0.0));
+    sim.step(10);
+
+    let (state, color) = sim
+        .client_boss(0, boss)
+        .expect("The boss should reach the client");
+    assert_eq!(state.phase, 0);
+    assert_eq!(color, TeamColor::Red);
+}
@@ -0,0 +1,196 @@
+use bevy::ecs::world::World;
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_shared::director::{Director, DirectorConfig, Verdict, DECISIONS_KEPT};
+use schizoid_shared::enemies::formations::Formation;
+use schizoid_shared::waves::{AuthoredWave, GroupColor, SpawnGroup, WaveTable};
+use schizoid_test_harness::sim::GameSim;
+
+fn clear_enemies(sim: &mut GameSim) {
+    for enemy in sim.enemy_entities() {
+        sim.despawn(enemy);
+    }
+}
+
+/// Let the red ship die in wave 1, then clear it and wait out the breather.
+fn struggle_through_wave_one(sim: &mut GameSim) {
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.run_waves(WaveTable::default());
+    sim.step(1);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 20.0), Vec2::ZERO);
+    sim.step(1);
+    assert!(sim.is_dead(red));
+
+    clear_enemies(sim);
+    sim.step_secs(WAVE_BREATHER + 0.2);
+    assert_eq!(sim.wave_state().current_wave, 2);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+}
+
+const RED_SHIP: Vec2 = Vec2::new(-200.0, 0.0);
+const BLUE_SHIP: Vec2 = Vec2::new(200.0, 0.0);
+
+/// Two chasers of each color behind the ship of their color, so they run
+/// into it on their way to the other ship and die fast.
+fn easy_wave() -> AuthoredWave {
+    let group = |color, x| SpawnGroup {
+        archetype: "chaser".to_string(),
+        count: 2,
+        color,
+        formation: Formation::Cluster {
+            at: Vec2::new(x, 0.0),
+            spread: 0.0,
+        },
+        delay: 0.0,
+    };
+    AuthoredWave {
+        groups: vec![
+            group(GroupColor::Red, -400.0),
+            group(GroupColor::Blue, 400.0),
+        ],
+        breather: 0.1,
+    }
+}
+
+#[test]
+fn struggling_team_gets_relief() {
+    let mut sim = GameSim::with_seed(4);
+    struggle_through_wave_one(&mut sim);
+
+    let decisions = sim.director_decisions();
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].verdict, Verdict::Struggling);
+    assert_eq!(decisions[0].report.deaths, 1);
+
+    let difficulty = sim.difficulty();
+    assert!(difficulty.enemy_count < 1.0);
+    assert!(difficulty.speed < 1.0);
+    assert!(difficulty.chaser_share < 1.0);
+    let base = 2 * 3 + 2;
+    assert!(
+        (sim.enemy_count() as u32) < base,
+        "Wave 2 should be smaller than its base {base}"
+    );
+}
+
+#[test]
+fn eased_waves_spawn_slower_enemies() {
+    let mut sim = GameSim::with_seed(4);
+    struggle_through_wave_one(&mut sim);
+    let speed = DRIFTER_SPEED * sim.difficulty().speed;
+
+    let drifters: Vec<_> = sim
+        .enemy_entities()
+        .into_iter()
+        .filter(|&enemy| sim.archetype(enemy) == "drifter")
+        .collect();
+    assert!(
+        !drifters.is_empty(),
+        "Seed should give wave 2 some drifters"
+    );
+    for drifter in drifters {
+        assert!((sim.velocity(drifter).length() - speed).abs() < 1e-3);
+    }
+}
+
+#[test]
+fn cruising_team_is_pushed_harder() {
+    let mut sim = GameSim::with_seed(4);
+    sim.spawn_player(TeamColor::Red, RED_SHIP);
+    sim.spawn_player(TeamColor::Blue, BLUE_SHIP);
+    sim.run_waves(WaveTable {
+        waves: vec![easy_wave()],
+    });
+
+    sim.step_secs(SPAWN_TELEGRAPH + 1.5);
+
+    let decisions = sim.director_decisions();
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].verdict, Verdict::Cruising);
+    assert_eq!(decisions[0].report.red_kills, 2);
+    assert_eq!(decisions[0].report.blue_kills, 2);
+    assert!(sim.difficulty().enemy_count > 1.0);
+    assert!(sim.difficulty().speed > 1.0);
+}
+
+#[test]
+fn one_color_carrying_the_team_is_not_cruising() {
+    let mut sim = GameSim::with_seed(4);
+    sim.spawn_player(TeamColor::Red, RED_SHIP);
+    sim.spawn_player(TeamColor::Blue, BLUE_SHIP);
+    let mut wave = easy_wave();
+    wave.groups.truncate(1);
+    sim.run_waves(WaveTable { waves: vec![wave] });
+
+    sim.step_secs(SPAWN_TELEGRAPH + 1.5);
+
+    let decisions = sim.director_decisions();
+    assert_eq!(decisions.len(), 1);
+    assert_eq!(decisions[0].verdict, Verdict::Steady);
+    assert_eq!(decisions[0].difficulty, sim.difficulty());
+    assert_eq!(sim.difficulty().enemy_count, 1.0);
+}
+
+#[test]
+fn relief_stops_at_the_configured_bounds() {
+    let mut sim = GameSim::with_seed(4);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.run_waves(WaveTable::default());
+    sim.set_director(DirectorConfig {
+        step: 0.5,
+        ..Default::default()
+    });
+    let min = DirectorConfig::default().min;
+
+    for wave in 1..=2 {
+        sim.step(1);
+        sim.step_secs(SPAWN_BATCH_INTERVAL * 2.0);
+        sim.spawn_drifter(TeamColor::Blue, sim.position(red), Vec2::ZERO);
+        sim.step(1);
+        clear_enemies(&mut sim);
+        sim.step_secs(RESPAWN_TIME + INVULNERABLE_TIME + 0.2);
+        assert_eq!(sim.wave_state().current_wave, wave + 1);
+    }
+
+    assert_eq!(sim.director_decisions().len(), 2);
+    assert_eq!(sim.difficulty(), min);
+}
+
+#[test]
+fn disabled_director_keeps_base_difficulty() {
+    let mut sim = GameSim::with_seed(4);
+    sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.run_waves(WaveTable::default());
+    sim.set_director(DirectorConfig {
+        enabled: false,
+        ..Default::default()
+    });
+    sim.step(1);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 20.0), Vec2::ZERO);
+    sim.step(1);
+    clear_enemies(&mut sim);
+    sim.step_secs(WAVE_BREATHER + 0.2);
+    sim.step_secs(SPAWN_BATCH_INTERVAL);
+
+    assert!(sim.director_decisions().is_empty());
+    assert_eq!(sim.enemy_count(), 2 * 3 + 2);
+}
+
+#[test]
+fn only_the_latest_decisions_are_kept() {
+    let room = World::new().spawn_empty().id();
+    let mut director = Director::new(DirectorConfig::default());
+    for wave in 1..=DECISIONS_KEPT as u32 + 10 {
+        director.wave_started(room, wave);
+        director.wave_cleared(room, "test");
+    }
+
+    assert_eq!(director.decisions.len(), DECISIONS_KEPT);
+    assert_eq!(director.decisions.front().unwrap().report.wave, 11);
+    assert_eq!(
+        director.decisions.back().unwrap().report.wave,
+        DECISIONS_KEPT as u32 + 10
+    );
+}
@@ -0,0 +1,205 @@
+use bevy::math::Vec2;
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn leaving_the_lobby_frees_the_seat() {
+    let mut sim = GameSim::networked_lobby(
+        2,
+        MatchRules {
+            countdown: 0.0,
+            ..Default::default()
+        },
+    );
+    sim.join_room(0, "alpha");
+    sim.step(30);
+    let ship = sim.player(0);
+
+    sim.disconnect(0);
+    sim.step(10);
+
+    assert!(!sim.entity_exists(ship));
+    assert!(
+        !sim.room_open("alpha"),
+        "A room whose last seat is freed should close"
+    );
+}
+
+#[test]
+fn dropping_out_mid_match_parks_the_ship() {
+    let mut sim = GameSim::networked(2);
+    let blue = sim.player(1);
+    sim.spawn_player(TeamColor::Blue, Vec2::new(100.0, 0.0));
+    sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+
+    sim.disconnect(1);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(100.0, 0.0), Vec2::ZERO);
+    sim.step(30);
+
+    assert!(sim.is_parked(blue));
+    assert!(sim.is_alive(blue), "A parked ship can't be hit");
+    assert_eq!(sim.position(blue), Vec2::new(100.0, 0.0));
+    let players = sim.room_players("test");
+    assert_eq!(players.len(), 2);
+    assert!(!players[1].connected);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+}
+
+#[test]
+fn reconnecting_gets_the_old_ship_and_score_back() {
+    let mut sim = GameSim::networked(2);
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::ZERO);
+    sim.step(5);
+    let score = sim.score(blue);
+    assert!(score > 0);
+
+    sim.disconnect(1);
+    sim.reconnect(1);
+    sim.step(30);
+
+    assert_eq!(sim.player(1), blue);
+    assert!(!sim.is_parked(blue));
+    assert_eq!(sim.score(blue), score);
+    assert!(sim.room_players("test")[1].connected);
+
+    let start = sim.position(blue);
+    sim.set_input(blue, Vec2::new(1.0, 0.0));
+    sim.step(30);
+    assert!(
+        sim.position(blue).x > start.x + 20.0,
+        "The returning client should fly its ship again"
+    );
+}
+
+#[test]
+fn a_new_player_can_take_over_an_abandoned_ship() {
+    let mut sim = GameSim::networked_lobby(
+        3,
+        MatchRules {
+            countdown: 0.0,
+            ..Default::default()
+        },
+    );
+    for client in 0..2 {
+        sim.join_room(client, "alpha");
+        sim.step(30);
+        sim.set_ready(client, true);
+        sim.step(30);
+    }
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    let blue = sim.player(1);
+
+    sim.disconnect(1);
+    sim.join_room(2, "alpha");
+    sim.step(30);
+
+    assert_eq!(sim.player(2), blue);
+    assert!(!sim.is_parked(blue));
+    let colors: Vec<_> = sim
+        .room_players("alpha")
+        .iter()
+        .map(|player| (player.color, player.connected))
+        .collect();
+    assert_eq!(
+        colors,
+        vec![(TeamColor::Red, true), (TeamColor::Blue, true)]
+    );
+}
+
+#[test]
+fn a_rematch_drops_players_who_left() {
+    let mut sim = GameSim::networked_with_rules(
+        2,
+        MatchRules {
+            countdown: 0.0,
+            ..Default::default()
+        },
+    );
+    sim.step(5);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    let blue = sim.player(1);
+    sim.disconnect(1);
+
+    sim.set_lives(1);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::ZERO);
+    sim.step(5);
+    assert!(sim.is_dead(red));
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+
+    sim.request_rematch(0);
+    sim.step(20);
+
+    assert!(!sim.entity_exists(blue));
+    assert_eq!(sim.room_players("test").len(), 1);
+    assert_eq!(
+        sim.match_state(),
+        MatchState::Lobby,
+        "The match should wait for a new partner"
+    );
+}
+
+/// A match that starts straight away and holds a deserted room open for
+/// a second.
+fn short_grace() -> MatchRules {
+    MatchRules {
+        players: 1,
+        countdown: 0.0,
+        reconnect_grace: 1.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn a_match_everyone_dropped_out_of_closes_after_the_grace() {
+    let mut sim = GameSim::networked_with_rules(2, short_grace());
+    sim.step(5);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    let red = sim.player(0);
+    let drifter = sim.spawn_drifter(TeamColor::Red, Vec2::new(300.0, 300.0), Vec2::ZERO);
+
+    sim.disconnect(0);
+    sim.step(10);
+    assert!(sim.entity_exists(red), "Blue is still playing");
+
+    sim.disconnect(1);
+    sim.step(10);
+    assert!(
+        sim.room_open("test"),
+        "The room should wait for its players to come back"
+    );
+    assert!(sim.is_parked(red));
+
+    sim.step(120);
+
+    assert!(!sim.entity_exists(red));
+    assert!(!sim.entity_exists(drifter));
+    assert!(
+        !sim.room_open("test"),
+        "Nobody came back, so the room should close"
+    );
+}
+
+#[test]
+fn a_player_can_come_back_after_everyone_dropped_out() {
+    let mut sim = GameSim::networked_with_rules(2, short_grace());
+    sim.step(5);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    let blue = sim.player(1);
+
+    sim.disconnect(0);
+    sim.disconnect(1);
+    sim.step(10);
+    sim.reconnect(1);
+    sim.step(120);
+
+    assert!(sim.room_open("test"), "Blue came back in time");
+    assert_eq!(sim.player(1), blue);
+    assert!(!sim.is_parked(blue));
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    let players = sim.room_players("test");
+    assert!(!players[0].connected);
+    assert!(players[1].connected);
+}
@@ -0,0 +1,162 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_shared::enemies::formations::{Axis, Formation, Side};
+use schizoid_shared::waves::{AuthoredWave, GroupColor, SpawnGroup, WaveTable};
+use schizoid_test_harness::sim::GameSim;
+
+/// Edges of the band enemies spawn in, just inside the default arena's walls.
+const INNER_X: f32 = 580.0;
+const INNER_Y: f32 = 380.0;
+
+#[test]
+fn ring_closes_on_the_centre() {
+    let mut sim = GameSim::with_seed(2);
+    let enemies = sim.spawn_formation("drifter", 6, Formation::Ring { radius: 300.0 });
+
+    assert_eq!(enemies.len(), 6);
+    let positions: Vec<_> = enemies.iter().map(|&enemy| sim.position(enemy)).collect();
+    for (index, &enemy) in enemies.iter().enumerate() {
+        let pos = positions[index];
+        assert!((pos.length() - 300.0).abs() < 1e-3);
+        let next = positions[(index + 1) % positions.len()];
+        assert!(
+            (pos.distance(next) - 300.0).abs() < 1e-2,
+            "Six around a circle should sit a radius apart"
+        );
+        let inward = -pos.normalize() * DRIFTER_SPEED;
+        assert!(sim.velocity(enemy).distance(inward) < 1e-3);
+    }
+}
+
+#[test]
+fn formations_stay_inside_the_arena() {
+    let mut sim = GameSim::with_seed(2);
+    let formations = [
+        Formation::Ring { radius: 2000.0 },
+        Formation::Swarm {
+            at: Vec2::new(INNER_X, 0.0),
+            radius: 500.0,
+        },
+        Formation::Cluster {
+            at: Vec2::new(900.0, 900.0),
+            spread: -50.0,
+        },
+    ];
+    for formation in formations {
+        for enemy in sim.spawn_formation("drifter", 6, formation) {
+            let pos = sim.position(enemy);
+            assert!(
+                pos.x.abs() <= INNER_X && pos.y.abs() <= INNER_Y,
+                "{formation:?} placed an enemy at {pos}, outside the arena"
+            );
+        }
+    }
+}
+
+#[test]
+fn wall_alternates_colors_and_sweeps_across() {
+    let mut sim = GameSim::with_seed(2);
+    let enemies = sim.spawn_formation("drifter", 8, Formation::Wall { from: Side::Left });
+
+    assert_eq!(enemies.len(), 8);
+    let mut last_y = f32::NEG_INFINITY;
+    for (index, &enemy) in enemies.iter().enumerate() {
+        let expected = if index % 2 == 0 {
+            TeamColor::Red
+        } else {
+            TeamColor::Blue
+        };
+        assert_eq!(sim.color(enemy), expected);
+        let pos = sim.position(enemy);
+        assert_eq!(pos.x, -INNER_X);
+        assert!(pos.y > last_y, "The wall should run along the left wall");
+        last_y = pos.y;
+        assert_eq!(sim.velocity(enemy), Vec2::new(DRIFTER_SPEED, 0.0));
+    }
+}
+
+#[test]
+fn pincer_comes_from_both_sides() {
+    let mut sim = GameSim::with_seed(2);
+    let enemies = sim.spawn_formation(
+        "drifter",
+        5,
+        Formation::Pincer {
+            axis: Axis::Horizontal,
+        },
+    );
+
+    let (left, right): (Vec<_>, Vec<_>) = enemies
+        .iter()
+        .partition(|&&enemy| sim.position(enemy).x < 0.0);
+    assert_eq!((left.len(), right.len()), (3, 2));
+    for (side, heading) in [(&left, 1.0), (&right, -1.0)] {
+        let mean_y: f32 = side
+            .iter()
+            .map(|&&enemy| sim.position(enemy).y)
+            .sum::<f32>()
+            / side.len() as f32;
+        assert!(mean_y.abs() < 1e-3, "Each column should centre on its wall");
+        for &&enemy in side {
+            assert_eq!(sim.position(enemy).x.abs(), INNER_X);
+            assert_eq!(sim.velocity(enemy), Vec2::new(heading * DRIFTER_SPEED, 0.0));
+        }
+    }
+}
+
+#[test]
+fn swarm_packs_tight_and_moves_as_one() {
+    let at = Vec2::new(0.0, 250.0);
+    let mut sim = GameSim::with_seed(2);
+    let enemies = sim.spawn_formation("drifter", 7, Formation::Swarm { at, radius: 40.0 });
+
+    let heading = sim.velocity(enemies[0]);
+    assert!((heading.length() - DRIFTER_SPEED).abs() < 1e-3);
+    for enemy in enemies {
+        assert!(sim.position(enemy).distance(at) <= 40.0);
+        assert_eq!(sim.velocity(enemy), heading);
+    }
+}
+
+#[test]
+fn formations_keep_clear_of_ships() {
+    let mut sim = GameSim::with_seed(2);
+    let ship_at = Vec2::new(300.0, 0.0);
+    sim.spawn_player(TeamColor::Red, ship_at);
+
+    let enemies = sim.spawn_formation("drifter", 6, Formation::Ring { radius: 300.0 });
+
+    for enemy in enemies {
+        assert!(sim.position(enemy).distance(ship_at) >= SPAWN_SAFE_DISTANCE - 1e-3);
+    }
+}
+
+#[test]
+fn waves_spawn_groups_in_formation() {
+    let mut sim = GameSim::with_seed(2);
+    sim.run_waves(WaveTable {
+        waves: vec![AuthoredWave {
+            groups: vec![SpawnGroup {
+                archetype: "drifter".to_string(),
+                count: 4,
+                color: GroupColor::Random,
+                formation: Formation::Wall { from: Side::Top },
+                delay: 0.0,
+            }],
+            breather: WAVE_BREATHER,
+        }],
+    });
+
+    sim.step(1);
+
+    let enemies = sim.enemies();
+    assert_eq!(enemies.len(), 4);
+    let reds = enemies
+        .iter()
+        .filter(|(_, color, _)| *color == TeamColor::Red)
+        .count();
+    assert_eq!(reds, 2, "Walls alternate colors whatever the group says");
+    for (_, _, pos) in enemies {
+        assert_eq!(pos.y, 380.0);
+    }
+}
@@ -0,0 +1,86 @@
+use schizoid_client::gamepads::PadAssignments;
+use schizoid_shared::components::TeamColor;
+use std::path::PathBuf;
+
+/// A fresh assignments file for one test.
+fn assignments_file(test: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "schizoid-gamepads-{test}-{}.json",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn identical_pads_get_their_own_keys() {
+    assert_eq!(
+        PadAssignments::key("Xbox Controller", &[]),
+        "Xbox Controller"
+    );
+    assert_eq!(
+        PadAssignments::key("Xbox Controller", &["Xbox Controller"]),
+        "Xbox Controller #2"
+    );
+    assert_eq!(
+        PadAssignments::key("Xbox Controller", &["Xbox Controller #2"]),
+        "Xbox Controller",
+        "A pad reconnecting takes the first free number"
+    );
+    assert_eq!(
+        PadAssignments::key("DualSense", &["Xbox Controller"]),
+        "DualSense"
+    );
+}
+
+#[test]
+fn new_pads_take_blue_then_red() {
+    let path = assignments_file("new");
+    let assignments = PadAssignments::load(&path);
+
+    assert_eq!(assignments.pick("Pad", &[]), Some(TeamColor::Blue));
+    assert_eq!(
+        assignments.pick("Pad #2", &[TeamColor::Blue]),
+        Some(TeamColor::Red)
+    );
+    assert_eq!(
+        assignments.pick("Pad #3", &[TeamColor::Blue, TeamColor::Red]),
+        None,
+        "Both ships already have a pad"
+    );
+}
+
+#[test]
+fn pads_get_their_last_color_back_unless_taken() {
+    let path = assignments_file("last");
+    let mut assignments = PadAssignments::load(&path);
+    assignments.set("Pad", TeamColor::Red);
+
+    assert_eq!(assignments.pick("Pad", &[]), Some(TeamColor::Red));
+    assert_eq!(
+        assignments.pick("Pad", &[TeamColor::Red]),
+        Some(TeamColor::Blue)
+    );
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn swaps_are_remembered_across_launches() {
+    let path = assignments_file("swap");
+    let mut assignments = PadAssignments::load(&path);
+    assert_eq!(assignments.color("Pad"), None, "Missing file starts empty");
+    assignments.set("Pad", TeamColor::Blue);
+    assignments.set("Pad #2", TeamColor::Red);
+
+    assert_eq!(assignments.swap("Pad", TeamColor::Blue), TeamColor::Red);
+    assert_eq!(assignments.swap("Pad #2", TeamColor::Red), TeamColor::Blue);
+
+    let reloaded = PadAssignments::load(&path);
+    assert_eq!(reloaded.color("Pad"), Some(TeamColor::Red));
+    assert_eq!(
+        reloaded.color("Pad #2"),
+        Some(TeamColor::Blue),
+        "Identical pads keep separate colors"
+    );
+    std::fs::remove_file(&path).unwrap();
+}
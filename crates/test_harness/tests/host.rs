@@ -0,0 +1,48 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn a_host_plays_without_a_network() {
+    let mut sim = GameSim::hosted(false);
+    let ships = sim.host_ships();
+    assert_eq!(ships.len(), 1);
+    assert_eq!(sim.color(ships[0]), TeamColor::Red);
+
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.set_input(red, Vec2::new(0.0, 1.0));
+    sim.step(30);
+
+    assert!(
+        sim.position(red).y > 50.0,
+        "The host steers its ship directly, got {:?}",
+        sim.position(red)
+    );
+}
+
+#[test]
+fn a_solo_host_flies_both_ships() {
+    let mut sim = GameSim::hosted(true);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::ZERO);
+
+    sim.set_input(red, Vec2::new(-1.0, 0.0));
+    sim.set_input(blue, Vec2::new(1.0, 0.0));
+    sim.step(30);
+
+    assert!(sim.position(red).x < -50.0);
+    assert!(sim.position(blue).x > 50.0);
+}
+
+#[test]
+fn a_hosted_match_plays_out_to_game_over() {
+    let mut sim = GameSim::hosted(false);
+    sim.set_lives(1);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::ZERO);
+    sim.step(5);
+
+    assert!(sim.is_dead(red));
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+    assert_eq!(sim.leaderboard().len(), 1);
+}
@@ -0,0 +1,29 @@
+use schizoid_client::identity::{client_id, load_client_id};
+
+#[test]
+fn clients_launched_with_default_args_get_their_own_ids() {
+    assert_ne!(client_id(None, None), client_id(None, None));
+    assert_eq!(client_id(Some(7), None), 7);
+}
+
+#[test]
+fn a_client_keeps_its_id_file_across_launches() {
+    let path = std::env::temp_dir().join(format!("schizoid-client-id-{}", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let id = client_id(None, Some(&path));
+    assert_eq!(
+        client_id(None, Some(&path)),
+        id,
+        "The saved id should be reused"
+    );
+
+    std::fs::write(&path, "not an id").unwrap();
+    let fresh = load_client_id(&path);
+    assert_eq!(
+        load_client_id(&path),
+        fresh,
+        "An unreadable file should be replaced"
+    );
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,124 @@
+use bevy::math::Vec2;
+use schizoid_server::leaderboard::{Leaderboard, LEADERBOARD_SIZE};
+use schizoid_shared::components::*;
+use schizoid_shared::protocol::{MatchResult, PlayerKills};
+use schizoid_test_harness::sim::GameSim;
+
+fn result(wave: u32, team_score: u32) -> MatchResult {
+    MatchResult {
+        wave,
+        team_score,
+        kills: vec![PlayerKills {
+            color: TeamColor::Red,
+            kills: 3,
+        }],
+        duration_secs: 42.0,
+        seed: 7,
+    }
+}
+
+#[test]
+fn match_ends_when_the_team_runs_out_of_lives() {
+    let mut sim = GameSim::networked(2);
+    sim.set_lives(2);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    sim.spawn_player(TeamColor::Blue, Vec2::new(200.0, 0.0));
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(-160.0, 0.0), Vec2::ZERO);
+    sim.step(10);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(30);
+    assert!(sim.leaderboard().is_empty(), "No life has been spent yet");
+
+    sim.set_input(red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, sim.position(red), Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(200.0, 0.0), Vec2::ZERO);
+    sim.step(5);
+
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+    let board = sim.leaderboard();
+    assert_eq!(board.len(), 1, "Game over should record one result");
+    assert_eq!(board[0].seed, sim.rng_seed());
+    assert_eq!(board[0].team_score, EnemyType::Drifter.points());
+    assert_eq!(
+        board[0].kills,
+        vec![
+            PlayerKills {
+                color: TeamColor::Red,
+                kills: 1
+            },
+            PlayerKills {
+                color: TeamColor::Blue,
+                kills: 0
+            },
+        ]
+    );
+}
+
+#[test]
+fn clients_receive_the_leaderboard() {
+    let mut sim = GameSim::networked(2);
+    sim.step(10);
+    for client in 0..sim.client_count() {
+        assert_eq!(
+            sim.client_leaderboard(client),
+            Some(Vec::new()),
+            "Client {client} should get the leaderboard on connect"
+        );
+    }
+
+    sim.set_lives(2);
+    for (color, x) in [(TeamColor::Red, -200.0), (TeamColor::Blue, 200.0)] {
+        let ship = sim.spawn_player(color, Vec2::new(x, 0.0));
+        sim.spawn_drifter(color.opposite(), sim.position(ship), Vec2::ZERO);
+    }
+    sim.step(30);
+
+    let board = sim.leaderboard();
+    assert_eq!(board.len(), 1);
+    for client in 0..sim.client_count() {
+        assert_eq!(
+            sim.client_leaderboard(client),
+            Some(board.clone()),
+            "Client {client} should get the updated leaderboard after game over"
+        );
+    }
+}
+
+#[test]
+fn leaderboard_ranks_by_wave_then_score() {
+    let mut board = Leaderboard::in_memory();
+    board.record(result(3, 500));
+    board.record(result(5, 100));
+    board.record(result(3, 900));
+
+    let ranked: Vec<_> = board
+        .entries()
+        .iter()
+        .map(|entry| (entry.wave, entry.team_score))
+        .collect();
+    assert_eq!(ranked, vec![(5, 100), (3, 900), (3, 500)]);
+
+    for wave in 0..LEADERBOARD_SIZE as u32 {
+        board.record(result(wave + 10, 0));
+    }
+    assert_eq!(board.entries().len(), LEADERBOARD_SIZE);
+    assert_eq!(board.entries()[0].wave, LEADERBOARD_SIZE as u32 + 9);
+}
+
+#[test]
+fn leaderboard_persists_to_file() {
+    let path =
+        std::env::temp_dir().join(format!("schizoid-leaderboard-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut board = Leaderboard::load(&path);
+    assert!(
+        board.entries().is_empty(),
+        "Missing file should start empty"
+    );
+    board.record(result(4, 250));
+
+    let reloaded = Leaderboard::load(&path);
+    assert_eq!(reloaded.entries(), &[result(4, 250)]);
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,97 @@
+use bevy::math::Vec2;
+use bevy::prelude::Entity;
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+/// Kill the Red ship (the first client's) by dropping a Blue drifter on it.
+fn kill_red(sim: &mut GameSim) -> Entity {
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::ZERO);
+    sim.step(5);
+    red
+}
+
+#[test]
+fn lobby_waits_for_every_player() {
+    let mut sim = GameSim::networked_with_rules(
+        1,
+        MatchRules {
+            players: 2,
+            ..Default::default()
+        },
+    );
+    sim.step(30);
+
+    assert_eq!(sim.match_state(), MatchState::Lobby);
+    assert_eq!(sim.client_match_state(0), Some(MatchState::Lobby));
+}
+
+#[test]
+fn countdown_leads_into_play() {
+    let mut sim = GameSim::networked_with_rules(
+        2,
+        MatchRules {
+            countdown: 0.5,
+            ..Default::default()
+        },
+    );
+    assert!(
+        matches!(sim.match_state(), MatchState::Countdown { .. }),
+        "A full lobby should count down, got {:?}",
+        sim.match_state()
+    );
+
+    sim.step_secs(0.6);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+}
+
+#[test]
+fn each_death_costs_a_life() {
+    let mut sim = GameSim::networked(1);
+    let lives = sim.lives();
+
+    let red = kill_red(&mut sim);
+
+    assert!(sim.is_dead(red));
+    assert_eq!(sim.lives(), lives - 1);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+}
+
+#[test]
+fn last_life_ends_the_match_and_freezes_the_arena() {
+    let mut sim = GameSim::networked(1);
+    sim.set_lives(1);
+
+    let red = kill_red(&mut sim);
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+
+    let drifter = sim.spawn_drifter(
+        TeamColor::Blue,
+        Vec2::new(200.0, 0.0),
+        Vec2::new(100.0, 0.0),
+    );
+    sim.step_secs(RESPAWN_TIME + 0.5);
+
+    assert_eq!(sim.position(drifter), Vec2::new(200.0, 0.0));
+    assert!(sim.is_dead(red), "Ships should not respawn after game over");
+    assert_eq!(sim.client_match_state(0), Some(MatchState::GameOver));
+}
+
+#[test]
+fn rematch_resets_the_match() {
+    let mut sim = GameSim::networked(2);
+    sim.set_lives(1);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(0.0, 20.0), Vec2::ZERO);
+    let red = kill_red(&mut sim);
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+
+    sim.request_rematch(1);
+    sim.step(20);
+
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    assert_eq!(sim.lives(), MatchRules::default().lives);
+    assert!(sim.is_alive(red));
+    assert_eq!(sim.score(red), 0);
+    assert_eq!(sim.enemy_count(), 0, "Leftover enemies should be cleared");
+}
@@ -0,0 +1,154 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+fn momentum(walls: WallBehaviour) -> ShipHandling {
+    ShipHandling::Momentum(Momentum {
+        walls,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn momentum_ship_builds_up_speed() {
+    let mut direct = GameSim::new();
+    let mut drifting = GameSim::new();
+    drifting.set_handling(momentum(WallBehaviour::Slide));
+    let a = direct.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let b = drifting.spawn_player(TeamColor::Red, Vec2::ZERO);
+
+    direct.set_input(a, Vec2::new(1.0, 0.0));
+    drifting.set_input(b, Vec2::new(1.0, 0.0));
+    direct.step(3);
+    drifting.step(3);
+
+    assert!(
+        drifting.position(b).x < direct.position(a).x,
+        "Momentum should start slower than direct steering"
+    );
+
+    drifting.step_secs(1.0);
+    let speed = drifting.velocity(b).length();
+    assert!(
+        (speed - SHIP_SPEED).abs() < 1.0,
+        "Ship should reach its max speed, got {speed}"
+    );
+}
+
+#[test]
+fn momentum_ship_coasts_to_a_stop() {
+    let mut sim = GameSim::new();
+    sim.set_handling(momentum(WallBehaviour::Slide));
+    let ship = sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+
+    sim.set_input(ship, Vec2::new(1.0, 0.0));
+    sim.step_secs(0.5);
+    sim.set_input(ship, Vec2::ZERO);
+    let released = sim.position(ship).x;
+    sim.step_secs(2.0);
+
+    assert!(
+        sim.position(ship).x > released + 20.0,
+        "Ship should coast on after letting go"
+    );
+    assert!(
+        sim.velocity(ship).length() < 1.0,
+        "Friction should bring the ship to rest"
+    );
+}
+
+#[test]
+fn momentum_respects_a_custom_max_speed() {
+    let mut sim = GameSim::new();
+    sim.set_handling(ShipHandling::Momentum(Momentum {
+        max_speed: 150.0,
+        ..Default::default()
+    }));
+    let ship = sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+
+    sim.set_input(ship, Vec2::new(1.0, 0.0));
+    sim.step_secs(1.0);
+
+    assert!(sim.velocity(ship).length() <= 150.0 + 1e-3);
+}
+
+#[test]
+fn wall_stop_kills_all_velocity() {
+    let mut sim = GameSim::new();
+    sim.set_handling(momentum(WallBehaviour::Stop));
+    let ship = sim.spawn_player(TeamColor::Red, Vec2::new(550.0, 0.0));
+
+    sim.set_input(ship, Vec2::new(1.0, 1.0));
+    sim.step_secs(0.5);
+    sim.set_input(ship, Vec2::ZERO);
+    sim.step(1);
+
+    assert_eq!(
+        sim.velocity(ship),
+        Vec2::ZERO,
+        "Hitting the wall should stop the ship dead"
+    );
+}
+
+#[test]
+fn wall_slide_keeps_velocity_along_the_wall() {
+    let mut sim = GameSim::new();
+    sim.set_handling(momentum(WallBehaviour::Slide));
+    let ship = sim.spawn_player(TeamColor::Red, Vec2::new(550.0, 0.0));
+
+    sim.set_input(ship, Vec2::new(1.0, 1.0));
+    sim.step_secs(0.5);
+    sim.set_input(ship, Vec2::ZERO);
+    sim.step(1);
+
+    let vel = sim.velocity(ship);
+    assert_eq!(vel.x, 0.0, "Velocity into the wall should be lost");
+    assert!(vel.y > 0.0, "Ship should keep sliding along the wall");
+    assert!(sim.position(ship).x < 600.0 - SHIP_RADIUS + 1e-3);
+}
+
+#[test]
+fn momentum_is_independent_of_tick_rate() {
+    let travelled: Vec<f32> = [30.0, 60.0, 120.0]
+        .into_iter()
+        .map(|hz| {
+            let mut sim = GameSim::with_tick_rate(hz);
+            sim.set_handling(momentum(WallBehaviour::Slide));
+            let ship = sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+            sim.set_input(ship, Vec2::new(1.0, 0.0));
+            sim.step_secs(0.5);
+            sim.set_input(ship, Vec2::ZERO);
+            sim.step_secs(0.5);
+            sim.position(ship).x + 300.0
+        })
+        .collect();
+
+    for distance in &travelled {
+        assert!(
+            (distance - travelled[1]).abs() < 10.0,
+            "Distance should barely depend on tick rate, got {travelled:?}"
+        );
+    }
+}
+
+#[test]
+fn handling_replicates_and_prediction_tracks_server() {
+    let mut sim = GameSim::networked(1);
+    let ship = sim.player(0);
+    sim.set_handling(momentum(WallBehaviour::Slide));
+    sim.step(10);
+    assert_eq!(sim.client_handling(0), Some(momentum(WallBehaviour::Slide)));
+
+    sim.set_input(ship, Vec2::new(0.0, 1.0));
+    sim.step(20);
+    sim.set_input(ship, Vec2::ZERO);
+    sim.step(90);
+
+    let server = sim.position(ship);
+    assert!(server.y > 50.0, "Ship should have moved, got {server:?}");
+    let predicted = sim.predicted_position(ship);
+    assert!(
+        server.distance(predicted) < 1.0,
+        "Predicted ship should settle on the server position. Server: {server:?}, predicted: {predicted:?}"
+    );
+}
@@ -0,0 +1,59 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::{GameSim, NetworkCondition};
+
+#[test]
+fn same_color_kill_registers_under_good() {
+    same_color_kill(NetworkCondition::Good);
+}
+
+#[test]
+fn same_color_kill_registers_under_poor() {
+    same_color_kill(NetworkCondition::Poor);
+}
+
+fn same_color_kill(condition: NetworkCondition) {
+    let mut sim = GameSim::networked_with(2, condition);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    let enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(-100.0, 0.0), Vec2::ZERO);
+
+    sim.step(30);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(120);
+
+    assert!(
+        sim.is_dead(enemy),
+        "Same-color enemy should be destroyed under {:?}",
+        condition
+    );
+    assert!(
+        sim.is_alive(red),
+        "Ship should survive under {:?}",
+        condition
+    );
+}
+
+#[test]
+fn no_phantom_deaths_after_rollback() {
+    let mut sim = GameSim::networked_with(2, NetworkCondition::Poor);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    sim.spawn_player(TeamColor::Blue, Vec2::new(0.0, 300.0));
+    // Blue drifter passing just above the red ship's path: a near miss, never a hit
+    sim.spawn_drifter(
+        TeamColor::Blue,
+        Vec2::new(200.0, SHIP_RADIUS + ENEMY_RADIUS + 5.0),
+        Vec2::new(-DRIFTER_SPEED, 0.0),
+    );
+
+    sim.step(30);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+
+    for tick in 0..120 {
+        sim.step(1);
+        assert!(sim.is_alive(red), "Server should never kill the ship");
+        assert!(
+            sim.predicted_alive(red),
+            "Client predicted a death the server never confirmed (tick {tick})"
+        );
+    }
+}
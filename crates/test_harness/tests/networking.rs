@@ -0,0 +1,145 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn each_client_controls_one_ship() {
+    let mut sim = GameSim::networked(2);
+
+    let first = sim.player(0);
+    let second = sim.player(1);
+
+    assert_ne!(first, second, "Each client should own its own ship");
+}
+
+#[test]
+fn client_input_moves_server_ship() {
+    let mut sim = GameSim::networked(1);
+    let ship = sim.player(0);
+    let start = sim.position(ship);
+
+    sim.set_input(ship, Vec2::new(1.0, 0.0));
+    sim.step(60);
+
+    let pos = sim.position(ship);
+    assert!(
+        pos.x > start.x + 50.0,
+        "Server ship should follow client input. Start: {:?}, now: {:?}",
+        start,
+        pos
+    );
+}
+
+#[test]
+fn prediction_tracks_server() {
+    let mut sim = GameSim::networked(1);
+    let ship = sim.player(0);
+
+    sim.set_input(ship, Vec2::new(0.0, 1.0));
+    sim.step(30);
+    sim.set_input(ship, Vec2::ZERO);
+    sim.step(30);
+
+    let server = sim.position(ship);
+    let predicted = sim.predicted_position(ship);
+    assert!(
+        server.distance(predicted) < 1.0,
+        "Predicted ship should settle on the server position. Server: {:?}, predicted: {:?}",
+        server,
+        predicted
+    );
+}
+
+#[test]
+fn enemies_replicate_to_every_client() {
+    let mut sim = GameSim::networked(2);
+    let enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(300.0, 200.0), Vec2::ZERO);
+
+    sim.step(30);
+
+    for client in 0..sim.client_count() {
+        let pos = sim
+            .client_position(client, enemy)
+            .unwrap_or_else(|| panic!("Enemy should be replicated to client {client}"));
+        assert!(pos.distance(Vec2::new(300.0, 200.0)) < 1.0);
+    }
+}
+
+#[test]
+fn same_color_kill_over_network() {
+    let mut sim = GameSim::networked(2);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(40.0, 0.0), Vec2::ZERO);
+
+    sim.step(10);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(30);
+
+    assert!(sim.is_dead(enemy), "Same-color enemy should be destroyed");
+    assert!(
+        sim.is_alive(red),
+        "Ship should survive same-color collision"
+    );
+    sim.step(30);
+    assert!(
+        !sim.replicated_to(0, enemy) && !sim.replicated_to(1, enemy),
+        "Despawn should replicate to clients"
+    );
+}
+
+#[test]
+fn clients_share_the_match_seed() {
+    let mut sim = GameSim::networked(2);
+    sim.step(10);
+
+    let seed = sim.rng_seed();
+    for client in 0..sim.client_count() {
+        assert_eq!(
+            sim.client_rng_seed(client),
+            seed,
+            "Client {client} should seed its RNG from the server's match seed"
+        );
+    }
+}
+
+#[test]
+fn clients_see_their_partner_ship() {
+    let mut sim = GameSim::networked(2);
+    let partner = sim.player(1);
+    sim.step(30);
+
+    assert!(
+        sim.client_position(0, partner).is_some(),
+        "Client 0 should have a position for client 1's ship"
+    );
+}
+
+#[test]
+fn partner_ship_is_interpolated_not_predicted() {
+    let mut sim = GameSim::networked(2);
+    let own = sim.player(0);
+    let partner = sim.player(1);
+    sim.step(30);
+
+    assert!(
+        !sim.client_interpolates(0, own),
+        "Clients predict their own ship"
+    );
+    assert!(
+        sim.client_interpolates(0, partner),
+        "Client 0 has no input to predict client 1's ship with"
+    );
+    assert!(sim.client_interpolates(1, own));
+
+    sim.set_input(partner, Vec2::new(1.0, 0.0));
+    sim.step(30);
+    sim.set_input(partner, Vec2::ZERO);
+    sim.step(30);
+
+    let server = sim.position(partner);
+    let seen = sim.client_position(0, partner).unwrap();
+    assert!(
+        server.distance(seen) < 1.0,
+        "The partner should settle where the server has it. Server: {server:?}, seen: {seen:?}"
+    );
+}
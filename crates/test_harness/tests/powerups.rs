@@ -0,0 +1,130 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn touching_pickup_activates_rope() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let pickup = sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(5.0, 0.0));
+
+    sim.step(1);
+
+    assert!(!sim.entity_exists(pickup), "Pickup should be collected");
+    assert!(sim.rope_remaining().is_some(), "Rope should be active");
+}
+
+#[test]
+fn rope_kills_enemies_of_both_colors() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    sim.spawn_player(TeamColor::Blue, Vec2::new(200.0, 0.0));
+    let red_enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(-50.0, 5.0), Vec2::ZERO);
+    let blue_enemy = sim.spawn_drifter(TeamColor::Blue, Vec2::new(50.0, -5.0), Vec2::ZERO);
+    let clear_enemy = sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 100.0), Vec2::ZERO);
+
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(-200.0, 0.0));
+    sim.step(3);
+
+    assert!(
+        !sim.entity_exists(red_enemy),
+        "Rope should kill red enemies"
+    );
+    assert!(
+        !sim.entity_exists(blue_enemy),
+        "Rope should kill blue enemies"
+    );
+    assert!(
+        sim.entity_exists(clear_enemy),
+        "Enemies off the beam should survive"
+    );
+}
+
+#[test]
+fn rope_is_down_while_a_ship_is_dead() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::new(200.0, 0.0));
+    // Red enemy kills the blue ship
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(200.0, 0.0), Vec2::ZERO);
+    sim.step(1);
+    assert!(sim.is_dead(blue));
+
+    let enemy = sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 0.0), Vec2::ZERO);
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(-200.0, 0.0));
+    sim.step(3);
+
+    assert!(
+        sim.entity_exists(enemy),
+        "No beam should exist without both ships alive"
+    );
+}
+
+#[test]
+fn rope_expires() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::ZERO);
+
+    sim.step(1);
+    assert!(sim.rope_remaining().is_some());
+
+    sim.step_secs(ROPE_DURATION + 0.1);
+    assert!(sim.rope_remaining().is_none(), "Rope should run out");
+}
+
+#[test]
+fn segment_circle_check() {
+    use schizoid_shared::systems::segment_intersects_circle;
+
+    let a = Vec2::new(-10.0, 0.0);
+    let b = Vec2::new(10.0, 0.0);
+    assert!(segment_intersects_circle(a, b, Vec2::new(0.0, 2.0), 3.0));
+    assert!(!segment_intersects_circle(a, b, Vec2::new(0.0, 4.0), 3.0));
+    // Beyond the endpoints only the endpoint distance counts
+    assert!(!segment_intersects_circle(a, b, Vec2::new(14.0, 0.0), 3.0));
+    assert!(segment_intersects_circle(a, b, Vec2::new(12.0, 0.0), 3.0));
+}
+
+#[test]
+fn rope_splits_splitters() {
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    sim.spawn_player(TeamColor::Blue, Vec2::new(200.0, 0.0));
+    let splitter = sim.spawn_splitter(TeamColor::Blue, Vec2::new(0.0, 5.0), Vec2::ZERO);
+
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(-200.0, 0.0));
+    for _ in 0..5 {
+        sim.step(1);
+        if !sim.entity_exists(splitter) {
+            break;
+        }
+    }
+
+    assert!(
+        !sim.entity_exists(splitter),
+        "The beam should cut the splitter"
+    );
+    let children = sim.enemy_entities();
+    assert_eq!(children.len(), 2, "A roped splitter should still split");
+    for child in children {
+        assert!(
+            sim.velocity(child).y > 0.0,
+            "Children should fly off the side of the beam the splitter was on"
+        );
+    }
+}
+
+#[test]
+fn rope_kills_score_for_the_ship_of_the_enemys_color() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::new(-200.0, 0.0));
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::new(200.0, 0.0));
+    sim.spawn_drifter(TeamColor::Blue, Vec2::new(50.0, 0.0), Vec2::ZERO);
+
+    sim.spawn_powerup(PowerUp::ElectricRope, Vec2::new(-200.0, 0.0));
+    sim.step(3);
+
+    assert_eq!(sim.score(blue), EnemyType::Drifter.points());
+    assert_eq!(sim.score(red), 0);
+}
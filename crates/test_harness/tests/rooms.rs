@@ -0,0 +1,160 @@
+use bevy::math::Vec2;
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::components::*;
+use schizoid_shared::protocol::Rejected;
+use schizoid_test_harness::sim::GameSim;
+
+fn instant_rules() -> MatchRules {
+    MatchRules {
+        countdown: 0.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn clients_have_no_ship_until_they_join() {
+    let mut sim = GameSim::networked_lobby(1, instant_rules());
+    sim.step(30);
+    assert!(!sim.seated(0));
+
+    sim.join_room(0, "alpha");
+    sim.step(30);
+
+    assert!(sim.seated(0));
+    let players = sim.room_players("alpha");
+    assert_eq!(players.len(), 1);
+    assert_eq!(players[0].color, TeamColor::Red);
+    assert!(!players[0].ready);
+}
+
+#[test]
+fn a_full_room_turns_the_third_player_away() {
+    let mut sim = GameSim::networked_lobby(3, instant_rules());
+    for client in 0..3 {
+        sim.join_room(client, "alpha");
+        sim.step(30);
+    }
+
+    assert!(sim.seated(0));
+    assert!(sim.seated(1));
+    assert!(!sim.seated(2), "A room only has two colors to hand out");
+    let colors: Vec<_> = sim
+        .room_players("alpha")
+        .iter()
+        .map(|player| player.color)
+        .collect();
+    assert_eq!(colors, vec![TeamColor::Red, TeamColor::Blue]);
+    assert_eq!(
+        sim.rejections(2),
+        vec![Rejected::RoomFull("alpha".to_string())]
+    );
+}
+
+#[test]
+fn the_match_starts_once_both_colors_are_ready() {
+    let mut sim = GameSim::networked_lobby(2, instant_rules());
+    sim.join_room(0, "alpha");
+    sim.step(30);
+    sim.join_room(1, "alpha");
+    sim.step(30);
+
+    sim.set_ready(0, true);
+    sim.step(30);
+    assert_eq!(sim.match_state(), MatchState::Lobby);
+
+    sim.set_ready(1, true);
+    sim.step(30);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+}
+
+#[test]
+fn players_can_take_a_free_color() {
+    let mut sim = GameSim::networked_lobby(1, instant_rules());
+    sim.join_room(0, "alpha");
+    sim.step(30);
+
+    sim.choose_color(0, TeamColor::Blue);
+    sim.step(30);
+
+    let ship = sim.player(0);
+    assert_eq!(sim.color(ship), TeamColor::Blue);
+    assert_eq!(sim.room_players("alpha")[0].color, TeamColor::Blue);
+}
+
+#[test]
+fn rooms_do_not_share_entities() {
+    let mut sim = GameSim::networked_in_rooms(&["alpha", "beta"]);
+    let beta_ship = sim.player(1);
+
+    sim.select_room("alpha");
+    let alpha_ship = sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+    let drifter = sim.spawn_drifter(TeamColor::Blue, sim.position(beta_ship), Vec2::ZERO);
+    sim.step(30);
+
+    assert!(
+        sim.is_alive(beta_ship),
+        "Enemies should only hit ships in their own room"
+    );
+    assert!(sim.replicated_to(0, drifter));
+    assert!(!sim.replicated_to(1, drifter));
+    assert!(!sim.replicated_to(1, alpha_ship));
+}
+
+#[test]
+fn game_over_in_one_room_leaves_the_other_playing() {
+    let mut sim = GameSim::networked_in_rooms(&["alpha", "beta"]);
+
+    sim.select_room("alpha");
+    sim.set_lives(1);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::ZERO);
+    sim.step(5);
+    assert!(sim.is_dead(red));
+    assert_eq!(sim.match_state(), MatchState::GameOver);
+
+    sim.select_room("beta");
+    assert_eq!(sim.match_state(), MatchState::Playing);
+    assert_eq!(sim.client_match_state(1), Some(MatchState::Playing));
+}
+
+#[test]
+fn players_can_ask_for_a_color_when_joining() {
+    let mut sim = GameSim::networked_lobby(1, instant_rules());
+    sim.join_room_as(0, "alpha", TeamColor::Blue);
+    sim.step(30);
+
+    let ship = sim.player(0);
+    assert_eq!(sim.color(ship), TeamColor::Blue);
+    assert!(sim.rejections(0).is_empty());
+}
+
+#[test]
+fn joining_in_a_taken_color_is_rejected() {
+    let mut sim = GameSim::networked_lobby(2, instant_rules());
+    sim.join_room_as(0, "alpha", TeamColor::Blue);
+    sim.step(30);
+
+    sim.join_room_as(1, "alpha", TeamColor::Blue);
+    sim.step(30);
+
+    assert!(
+        !sim.seated(1),
+        "A taken color should not be handed out twice"
+    );
+    assert_eq!(
+        sim.rejections(1),
+        vec![Rejected::ColorTaken(TeamColor::Blue)]
+    );
+}
+
+#[test]
+fn colors_cannot_change_mid_match() {
+    let mut sim = GameSim::networked(1);
+    let ship = sim.player(0);
+
+    sim.choose_color(0, TeamColor::Blue);
+    sim.step(30);
+
+    assert_eq!(sim.color(ship), TeamColor::Red);
+    assert_eq!(sim.rejections(0), vec![Rejected::MatchUnderway]);
+}
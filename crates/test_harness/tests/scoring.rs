@@ -0,0 +1,90 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn kill_awards_points_by_enemy_type() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_chaser(TeamColor::Red, Vec2::new(20.0, 0.0));
+
+    sim.step(1);
+
+    assert_eq!(sim.score(red), EnemyType::Chaser.points());
+}
+
+#[test]
+fn quick_kills_build_a_combo() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(20.0, 0.0), Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(100.0, 0.0), Vec2::ZERO);
+
+    sim.step(1);
+    assert_eq!(sim.multiplier(red), 2, "First kill should start a combo");
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(20);
+
+    let drifter = EnemyType::Drifter.points();
+    assert_eq!(
+        sim.score(red),
+        drifter + drifter * 2,
+        "Second kill inside the window should be doubled"
+    );
+}
+
+#[test]
+fn combo_decays_over_time() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(20.0, 0.0), Vec2::ZERO);
+
+    sim.step(1);
+    assert_eq!(sim.multiplier(red), 2);
+
+    sim.step_secs(COMBO_WINDOW + 0.1);
+    assert_eq!(
+        sim.multiplier(red),
+        1,
+        "Combo should lapse after the window"
+    );
+}
+
+#[test]
+fn death_resets_combo_but_keeps_points() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(20.0, 0.0), Vec2::ZERO);
+
+    sim.step(1);
+    let points = sim.score(red);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 20.0), Vec2::ZERO);
+    sim.step(1);
+
+    assert!(sim.is_dead(red));
+    assert_eq!(sim.multiplier(red), 1, "Death should reset the combo");
+    assert_eq!(sim.score(red), points, "Death should not cost points");
+}
+
+#[test]
+fn score_replicates_to_clients() {
+    let mut sim = GameSim::networked(2);
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Red, Vec2::new(40.0, 0.0), Vec2::ZERO);
+
+    sim.step(10);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(30);
+
+    let points = sim.score(red);
+    assert!(points > 0, "Server should award the kill");
+    sim.step(30);
+    for client in 0..sim.client_count() {
+        assert_eq!(
+            sim.client_score(client, red),
+            Some(points),
+            "Client {client} should see the server's score"
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+use std::f32::consts::{FRAC_PI_2, PI};
+
+#[test]
+fn shield_front_bounces_same_color_ship() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let shielder = sim.spawn_shielder(TeamColor::Red, Vec2::new(80.0, 0.0), PI);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(15);
+
+    assert!(
+        sim.entity_exists(shielder),
+        "Shield front should protect the shielder"
+    );
+    assert!(sim.is_alive(red), "Bounced ship should survive");
+    assert!(
+        sim.distance(red, shielder) >= SHIP_RADIUS + SHIELDER_RADIUS,
+        "Ship should be pushed clear of the shield"
+    );
+}
+
+#[test]
+fn shield_front_bounces_opposite_color_ship() {
+    let mut sim = GameSim::new();
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::ZERO);
+    let shielder = sim.spawn_shielder(TeamColor::Red, Vec2::new(80.0, 0.0), PI);
+
+    sim.set_input(blue, Vec2::new(1.0, 0.0));
+    sim.step(15);
+
+    assert!(sim.entity_exists(shielder));
+    assert!(sim.is_alive(blue), "The shield bounces, it does not kill");
+}
+
+#[test]
+fn same_color_hit_from_behind_destroys_shielder() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    // Facing away from the ship
+    let shielder = sim.spawn_shielder(TeamColor::Red, Vec2::new(60.0, 0.0), 0.0);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(15);
+
+    assert!(
+        sim.is_dead(shielder),
+        "Same-color hit from behind should destroy the shielder"
+    );
+    assert!(sim.is_alive(red));
+}
+
+#[test]
+fn shielder_turns_toward_nearest_ship() {
+    let mut sim = GameSim::new();
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::new(0.0, 300.0));
+    let shielder = sim.spawn_shielder(TeamColor::Red, Vec2::ZERO, 0.0);
+
+    sim.step(20);
+    let partway = sim.facing(shielder);
+    assert!(
+        partway > 0.0 && partway < FRAC_PI_2 - 0.1,
+        "Turning should be rate limited, facing: {}",
+        partway
+    );
+
+    sim.step(60);
+    let to_ship = (sim.position(blue) - sim.position(shielder)).to_angle();
+    assert!(
+        (sim.facing(shielder) - to_ship).abs() < 0.1,
+        "Shielder should end up facing the ship. Facing: {}, ship at: {}",
+        sim.facing(shielder),
+        to_ship
+    );
+}
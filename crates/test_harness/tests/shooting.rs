@@ -0,0 +1,190 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::{GameSim, NetworkCondition};
+
+#[test]
+fn aiming_fires_along_the_aim() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+
+    sim.set_aim(red, Vec2::new(0.0, 2.0));
+    sim.step(1);
+
+    let shots = sim.projectiles();
+    assert_eq!(shots.len(), 1, "Aiming should fire a shot");
+    let velocity = sim.velocity(shots[0]);
+    assert!(velocity.x.abs() < 1e-3 && velocity.y > 0.0);
+    assert!((velocity.length() - PROJECTILE_SPEED).abs() < 1e-3);
+    assert_eq!(sim.color(shots[0]), TeamColor::Red);
+}
+
+#[test]
+fn no_aim_holds_fire() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(30);
+
+    assert!(sim.projectiles().is_empty(), "Moving alone should not fire");
+}
+
+#[test]
+fn cooldown_limits_fire_rate() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step_secs(0.5);
+
+    let fired = sim.projectiles().len() as f32;
+    let allowed = (0.5 / FIRE_COOLDOWN).ceil();
+    assert!(fired >= 2.0, "Holding aim should keep firing");
+    assert!(
+        fired <= allowed,
+        "Fired {fired} shots in 0.5s, at most {allowed} allowed"
+    );
+}
+
+#[test]
+fn shot_kills_same_color_enemy_and_scores() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(150.0, 0.0), Vec2::ZERO);
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(20);
+
+    assert!(!sim.entity_exists(enemy), "Shot should kill a red enemy");
+    assert_eq!(
+        sim.score(red),
+        EnemyType::Drifter.points(),
+        "The firing ship should get the kill"
+    );
+}
+
+#[test]
+fn shot_is_absorbed_by_opposite_color_enemy() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let blue_enemy = sim.spawn_drifter(TeamColor::Blue, Vec2::new(150.0, 0.0), Vec2::ZERO);
+    let red_enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(250.0, 0.0), Vec2::ZERO);
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(1);
+    sim.set_aim(red, Vec2::ZERO);
+    sim.step(30);
+
+    assert!(
+        sim.entity_exists(blue_enemy),
+        "Red shot should not kill a blue enemy"
+    );
+    assert!(
+        sim.entity_exists(red_enemy),
+        "The blue enemy should stop the shot"
+    );
+    assert!(sim.projectiles().is_empty());
+    assert_eq!(sim.score(red), 0);
+}
+
+#[test]
+fn shield_front_stops_shots() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    // Facing the ship, shield toward it
+    let shielder = sim.spawn_shielder(TeamColor::Red, Vec2::new(150.0, 0.0), std::f32::consts::PI);
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(20);
+
+    assert!(
+        sim.entity_exists(shielder),
+        "Shots into the shield should not kill"
+    );
+}
+
+#[test]
+fn shots_leave_the_arena() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(1);
+    sim.set_aim(red, Vec2::ZERO);
+    sim.step_secs(1.0);
+
+    assert!(
+        sim.projectiles().is_empty(),
+        "Shots should be gone once past the wall"
+    );
+}
+
+#[test]
+fn dead_ship_holds_fire() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_drifter(TeamColor::Blue, Vec2::new(0.0, 20.0), Vec2::ZERO);
+    sim.step(1);
+    assert!(sim.is_dead(red));
+
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    assert!(sim.projectiles().is_empty(), "Dead ships should not fire");
+}
+
+#[test]
+fn client_aim_fires_predicted_shots() {
+    let mut sim = GameSim::networked(1);
+    let red = sim.player(0);
+    let enemy = sim.spawn_drifter(TeamColor::Red, Vec2::new(500.0, 0.0), Vec2::ZERO);
+
+    sim.step(10);
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(20);
+    sim.set_aim(red, Vec2::ZERO);
+
+    let shot = *sim
+        .projectiles()
+        .first()
+        .expect("Server should fire for the client's aim");
+    sim.step(10);
+    assert!(sim.replicated_to(0, shot), "Client should receive the shot");
+
+    sim.step(60);
+    assert!(!sim.entity_exists(enemy), "Shot should kill the enemy");
+    assert_eq!(sim.score(red), EnemyType::Drifter.points());
+}
+
+#[test]
+fn client_sees_its_own_shot_before_the_server_fires_it() {
+    let mut sim = GameSim::networked_with(1, NetworkCondition::Average);
+    let red = sim.player(0);
+
+    sim.step(30);
+    sim.set_aim(red, Vec2::new(1.0, 0.0));
+    sim.step(2);
+
+    assert!(
+        sim.projectiles().is_empty(),
+        "The client's aim shouldn't have reached the server yet"
+    );
+    assert_eq!(
+        sim.client_projectile_count(0),
+        1,
+        "Client should show its shot as soon as it fires"
+    );
+
+    sim.set_aim(red, Vec2::ZERO);
+    sim.step(30);
+    assert_eq!(
+        sim.projectiles().len(),
+        1,
+        "Server should fire the same shot"
+    );
+    assert_eq!(
+        sim.client_projectile_count(0),
+        1,
+        "Server's shot should replace the client's, not double it"
+    );
+}
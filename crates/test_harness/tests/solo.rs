@@ -0,0 +1,63 @@
+use bevy::math::Vec2;
+use schizoid_server::lifecycle::MatchRules;
+use schizoid_shared::components::*;
+use schizoid_shared::protocol::Rejected;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn a_solo_player_holds_both_seats() {
+    let mut sim = GameSim::networked_solo();
+
+    let colors: Vec<_> = sim
+        .room_players("test")
+        .iter()
+        .map(|player| player.color)
+        .collect();
+    assert_eq!(colors, vec![TeamColor::Red, TeamColor::Blue]);
+    assert_eq!(sim.match_state(), MatchState::Playing);
+}
+
+#[test]
+fn each_ship_follows_its_own_input() {
+    let mut sim = GameSim::networked_solo();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let blue = sim.spawn_player(TeamColor::Blue, Vec2::ZERO);
+
+    sim.step(10);
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.set_input(blue, Vec2::new(-1.0, 0.0));
+    sim.step(30);
+
+    assert!(
+        sim.position(red).x > 50.0,
+        "Red should fly right, got {:?}",
+        sim.position(red)
+    );
+    assert!(
+        sim.position(blue).x < -50.0,
+        "Blue should fly left, got {:?}",
+        sim.position(blue)
+    );
+}
+
+#[test]
+fn going_solo_needs_an_empty_room() {
+    let mut sim = GameSim::networked_lobby(
+        2,
+        MatchRules {
+            countdown: 0.0,
+            ..Default::default()
+        },
+    );
+    sim.join_room(0, "alpha");
+    sim.step(30);
+
+    sim.join_solo(1, "alpha");
+    sim.step(30);
+
+    assert!(!sim.seated(1));
+    assert_eq!(
+        sim.rejections(1),
+        vec![Rejected::ColorTaken(TeamColor::Red)]
+    );
+}
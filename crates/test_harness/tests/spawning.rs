@@ -0,0 +1,158 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_shared::enemies::formations::Formation;
+use schizoid_shared::waves::{AuthoredWave, GroupColor, SpawnGroup, WaveTable};
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn wave_enemies_are_telegraphed_before_they_arrive() {
+    let mut sim = GameSim::with_seed(6);
+    sim.run_waves(WaveTable::default());
+    sim.step(1);
+
+    let enemies = sim.enemy_entities();
+    let spawned_at: Vec<_> = enemies.iter().map(|&enemy| sim.position(enemy)).collect();
+    for &enemy in &enemies {
+        assert!(sim.spawn_warning(enemy).is_some());
+    }
+
+    sim.step_secs(SPAWN_TELEGRAPH / 2.0);
+    let held: Vec<_> = enemies.iter().map(|&enemy| sim.position(enemy)).collect();
+    assert_eq!(held, spawned_at, "Telegraphed enemies should hold still");
+
+    sim.step_secs(SPAWN_TELEGRAPH / 2.0 + 0.1);
+    for &enemy in &enemies {
+        assert_eq!(sim.spawn_warning(enemy), None);
+    }
+    let moved: Vec<_> = enemies.iter().map(|&enemy| sim.position(enemy)).collect();
+    assert_ne!(moved, spawned_at, "Arrived enemies should move");
+}
+
+#[test]
+fn telegraphed_enemies_neither_hurt_nor_die() {
+    let mut sim = GameSim::with_seed(6);
+    sim.spawn_wave(1);
+    let enemies = sim.enemy_entities();
+    let (hurts, dies) = (enemies[0], enemies[1]);
+    let hurt = sim.spawn_player(sim.color(hurts).opposite(), sim.position(hurts));
+    let killer = sim.spawn_player(sim.color(dies), sim.position(dies));
+
+    sim.step(1);
+
+    assert!(sim.is_alive(hurt), "Telegraphed enemy should not kill");
+    assert!(sim.entity_exists(dies), "Telegraphed enemy should not die");
+    assert_eq!(sim.score(killer), 0);
+}
+
+#[test]
+fn enemies_collide_once_they_arrive() {
+    let mut sim = GameSim::with_seed(6);
+    sim.spawn_wave(1);
+    let enemy = sim.enemy_entities()[0];
+    let ship = sim.spawn_player(sim.color(enemy), sim.position(enemy));
+
+    sim.step(1);
+    assert!(sim.entity_exists(enemy));
+
+    sim.step_secs(SPAWN_TELEGRAPH);
+    assert!(
+        !sim.entity_exists(enemy),
+        "Same-color ship should destroy it"
+    );
+    assert!(sim.score(ship) > 0);
+}
+
+#[test]
+fn procedural_waves_arrive_in_batches() {
+    let mut sim = GameSim::with_seed(6);
+    sim.run_waves(WaveTable::default());
+
+    sim.step(1);
+    assert_eq!(sim.enemy_count(), SPAWN_BATCH_SIZE);
+
+    sim.step_secs(SPAWN_BATCH_INTERVAL / 2.0);
+    assert_eq!(sim.enemy_count(), SPAWN_BATCH_SIZE);
+
+    sim.step_secs(SPAWN_BATCH_INTERVAL / 2.0);
+    assert_eq!(sim.enemy_count(), 5, "The rest of wave 1 should arrive");
+    assert!(sim.wave_state().active);
+}
+
+#[test]
+fn wave_enemies_spawn_clear_of_living_ships() {
+    let ships = [
+        Vec2::ZERO,
+        Vec2::new(-560.0, 360.0),
+        Vec2::new(560.0, -360.0),
+        Vec2::new(0.0, 370.0),
+    ];
+    for seed in 0..5 {
+        let mut sim = GameSim::with_seed(seed);
+        for (index, &at) in ships.iter().enumerate() {
+            let color = if index % 2 == 0 {
+                TeamColor::Red
+            } else {
+                TeamColor::Blue
+            };
+            sim.spawn_player(color, at);
+        }
+
+        sim.spawn_wave(10);
+
+        for (_, _, pos) in sim.enemies() {
+            for ship in ships {
+                assert!(
+                    pos.distance(ship) >= SPAWN_SAFE_DISTANCE - 1e-3,
+                    "Enemy at {pos} is too close to the ship at {ship}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn authored_groups_on_a_ship_are_pushed_clear() {
+    let ship_at = Vec2::new(100.0, -50.0);
+    let mut sim = GameSim::new();
+    sim.spawn_player(TeamColor::Red, ship_at);
+    sim.run_waves(WaveTable {
+        waves: vec![AuthoredWave {
+            groups: vec![SpawnGroup {
+                archetype: "drifter".to_string(),
+                count: 3,
+                color: GroupColor::Blue,
+                formation: Formation::Cluster {
+                    at: ship_at,
+                    spread: 30.0,
+                },
+                delay: 0.0,
+            }],
+            breather: WAVE_BREATHER,
+        }],
+    });
+
+    sim.step(1);
+
+    let enemies = sim.enemies();
+    assert_eq!(enemies.len(), 3);
+    for (_, _, pos) in enemies {
+        assert!(pos.distance(ship_at) >= SPAWN_SAFE_DISTANCE - 1e-3);
+    }
+}
+
+#[test]
+fn spawn_warning_replicates_to_clients() {
+    let mut sim = GameSim::networked(1);
+    sim.spawn_wave(1);
+    let enemy = sim.enemy_entities()[0];
+
+    sim.step(20);
+
+    assert!(sim.replicated_to(0, enemy));
+    assert!(sim.client_spawn_warning(0, enemy).is_some());
+
+    sim.step_secs(SPAWN_TELEGRAPH);
+    if sim.entity_exists(enemy) {
+        assert_eq!(sim.client_spawn_warning(0, enemy), None);
+    }
+}
@@ -0,0 +1,112 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn same_color_kill_splits_into_two_children() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let splitter = sim.spawn_splitter(TeamColor::Red, Vec2::new(60.0, 0.0), Vec2::ZERO);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    assert!(sim.is_dead(splitter), "Splitter should be destroyed");
+    let children = sim.enemy_entities();
+    assert_eq!(children.len(), 2, "Splitter should leave two children");
+    for child in children {
+        assert!(
+            sim.radius(child) < SPLITTER_RADIUS,
+            "Children should be smaller than the splitter"
+        );
+    }
+}
+
+#[test]
+fn children_fly_apart_away_from_the_ship() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    sim.spawn_splitter(TeamColor::Red, Vec2::new(60.0, 0.0), Vec2::ZERO);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+    sim.set_input(red, Vec2::ZERO);
+
+    let children = sim.enemy_entities();
+    let (a, b) = (children[0], children[1]);
+    let before = sim.distance(a, b);
+    sim.step(10);
+
+    assert!(
+        sim.distance(a, b) > before,
+        "Children should separate. Before: {}, after: {}",
+        before,
+        sim.distance(a, b)
+    );
+    for child in [a, b] {
+        assert!(
+            sim.velocity(child).x > 0.0,
+            "Children should head away from the ship: {:?}",
+            sim.velocity(child)
+        );
+    }
+}
+
+#[test]
+fn opposite_color_splitter_kills_without_splitting() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let splitter = sim.spawn_splitter(TeamColor::Blue, Vec2::new(60.0, 0.0), Vec2::ZERO);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    assert!(
+        sim.is_dead(red),
+        "Opposite-color splitter should kill the ship"
+    );
+    assert!(sim.is_alive(splitter), "Splitter should survive");
+    assert_eq!(
+        sim.enemy_count(),
+        1,
+        "No children without a same-color kill"
+    );
+}
+
+#[test]
+fn child_colors_follow_the_match_seed() {
+    let colors = |seed: u64| {
+        let mut sim = GameSim::with_seed(seed);
+        let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+        sim.spawn_splitter(TeamColor::Red, Vec2::new(60.0, 0.0), Vec2::ZERO);
+        sim.set_input(red, Vec2::new(1.0, 0.0));
+        sim.step(10);
+        sim.enemies()
+            .into_iter()
+            .map(|(_, color, _)| color)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(colors(7), colors(7), "Same seed should split identically");
+}
+
+#[test]
+fn children_keep_the_splitters_speed_scale() {
+    let mut sim = GameSim::new();
+    let red = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+    let splitter = sim.spawn_splitter(TeamColor::Red, Vec2::new(60.0, 0.0), Vec2::ZERO);
+    sim.set_speed_scale(splitter, 1.5);
+
+    sim.set_input(red, Vec2::new(1.0, 0.0));
+    sim.step(10);
+
+    let children = sim.enemy_entities();
+    assert_eq!(children.len(), 2, "Splitter should leave two children");
+    for child in children {
+        let speed = sim.velocity(child).length();
+        assert!(
+            (speed - SPLITTER_CHILD_SPEED * 1.5).abs() < 0.01,
+            "Children should be sped up like their splitter: {speed}"
+        );
+    }
+}
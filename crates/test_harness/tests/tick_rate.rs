@@ -0,0 +1,69 @@
+use bevy::math::Vec2;
+use schizoid_shared::components::*;
+use schizoid_shared::parse_tick_rate;
+use schizoid_test_harness::sim::GameSim;
+
+const RATES: [f64; 3] = [30.0, 60.0, 120.0];
+
+#[test]
+fn ship_speed_is_independent_of_tick_rate() {
+    for hz in RATES {
+        let mut sim = GameSim::with_tick_rate(hz);
+        let ship = sim.spawn_player(TeamColor::Red, Vec2::new(-300.0, 0.0));
+
+        sim.set_input(ship, Vec2::new(1.0, 0.0));
+        sim.step_secs(1.0);
+
+        let travelled = sim.position(ship).x + 300.0;
+        assert!(
+            (travelled - SHIP_SPEED).abs() < 1.0,
+            "Ship should cover SHIP_SPEED units in one second at {hz} Hz, got {travelled}"
+        );
+    }
+}
+
+#[test]
+fn drifter_speed_is_independent_of_tick_rate() {
+    for hz in RATES {
+        let mut sim = GameSim::with_tick_rate(hz);
+        let drifter = sim.spawn_drifter(TeamColor::Blue, Vec2::ZERO, Vec2::new(0.0, DRIFTER_SPEED));
+
+        sim.step_secs(1.0);
+
+        let pos = sim.position(drifter);
+        assert!(
+            (pos.y - DRIFTER_SPEED).abs() < 1.0,
+            "Drifter should cover DRIFTER_SPEED units in one second at {hz} Hz, got {pos:?}"
+        );
+    }
+}
+
+#[test]
+fn respawn_takes_the_same_time_at_any_tick_rate() {
+    for hz in RATES {
+        let mut sim = GameSim::with_tick_rate(hz);
+        let ship = sim.spawn_player(TeamColor::Red, Vec2::ZERO);
+        let enemy = sim.spawn_drifter(TeamColor::Blue, Vec2::new(1.0, 0.0), Vec2::ZERO);
+
+        sim.step(1);
+        assert!(sim.is_dead(ship), "Ship should die on contact at {hz} Hz");
+        sim.despawn(enemy);
+
+        sim.step_secs(RESPAWN_TIME - 0.1);
+        assert!(sim.is_dead(ship), "Ship respawned early at {hz} Hz");
+
+        sim.step_secs(0.2);
+        assert!(sim.is_alive(ship), "Ship should respawn on time at {hz} Hz");
+    }
+}
+
+#[test]
+fn tick_rate_argument_must_be_a_sane_rate() {
+    assert_eq!(parse_tick_rate("120"), Ok(120.0));
+    for bad in ["0", "-60", "NaN", "inf", "1e9", "fast"] {
+        assert!(
+            parse_tick_rate(bad).is_err(),
+            "--tick-rate {bad} should be rejected"
+        );
+    }
+}
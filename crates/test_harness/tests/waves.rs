@@ -0,0 +1,36 @@
+use schizoid_test_harness::sim::GameSim;
+
+#[test]
+fn identical_seeds_spawn_identical_waves() {
+    let mut a = GameSim::with_seed(42);
+    let mut b = GameSim::with_seed(42);
+
+    for wave in 1..=3 {
+        a.spawn_wave(wave);
+        b.spawn_wave(wave);
+    }
+
+    assert_eq!(a.enemies(), b.enemies(), "Same seed should give same waves");
+}
+
+#[test]
+fn different_seeds_spawn_different_waves() {
+    let mut a = GameSim::with_seed(1);
+    let mut b = GameSim::with_seed(2);
+
+    a.spawn_wave(3);
+    b.spawn_wave(3);
+
+    assert_ne!(a.enemies(), b.enemies(), "Different seeds should differ");
+}
+
+#[test]
+fn wave_size_scales_with_wave_number() {
+    let mut sim = GameSim::with_seed(7);
+
+    sim.spawn_wave(1);
+    assert_eq!(sim.enemy_count(), 5);
+
+    sim.spawn_wave(2);
+    assert_eq!(sim.enemy_count(), 5 + 8);
+}